@@ -1,105 +1,122 @@
 // AI module - generates commit messages using AI APIs
 
+pub mod prompt;
+pub mod provider;
+pub mod redact;
+pub mod summarize;
+
 use anyhow::{bail, Context, Result};
-use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::Duration;
 
-#[derive(Debug, Serialize)]
-struct ChatMessage {
-    role: String,
-    content: String,
-}
+use crate::config::AiConfig;
+use prompt::PromptVars;
+use provider::{Completion, Provider};
+use summarize::Summary;
+
+/// Output of `ai.api_key_cmd`, keyed by the command, so it runs at most once per process
+static API_KEY_CMD_CACHE: Mutex<Option<(String, String)>> = Mutex::new(None);
 
-#[derive(Debug, Serialize)]
-struct ChatRequest {
+pub struct AiClient {
+    provider: Box<dyn Provider>,
+    endpoint: String,
+    api_key: Option<String>,
     model: String,
-    messages: Vec<ChatMessage>,
+    timeout: Duration,
+    /// Prompt template (`ai.prompt_file` or the built-in one)
+    template: String,
     temperature: f32,
     max_tokens: u32,
+    /// Keep a multi-line body
+    body: bool,
+    subject_length: usize,
 }
 
-#[derive(Debug, Deserialize)]
-struct ChatResponse {
-    choices: Vec<Choice>,
-}
-
-#[derive(Debug, Deserialize)]
-struct Choice {
-    message: Message,
-}
+impl AiClient {
+    /// Create a client from `ai.*` settings, filling in provider defaults.
+    /// Returns `None` when AI is not configured. `repo_path` anchors a relative `ai.prompt_file`.
+    pub fn from_config(config: &AiConfig, repo_path: &Path) -> Result<Option<Self>> {
+        let has_key_source = config.api_key.is_some() || config.api_key_env.is_some() || config.api_key_cmd.is_some();
+        if config.provider.is_none() && !has_key_source && config.endpoint.is_none() {
+            return Ok(None);
+        }
 
-#[derive(Debug, Deserialize)]
-struct Message {
-    content: String,
-}
+        let provider = provider::by_name(config.provider.as_deref())?;
+        let api_key = resolve_api_key(config)?;
+        if provider.requires_api_key() && api_key.is_none() {
+            bail!("An API key is required for the {} provider \
+                (set ai.api_key_env, ai.api_key_cmd or 'gfv config --credentials ai.api_key <key>')",
+                provider.name());
+        }
 
-pub struct AiClient {
-    endpoint: String,
-    api_key: String,
-    model: String,
-}
+        let template = match config.prompt_file {
+            Some(ref file) => prompt::load_template(file, repo_path)?,
+            None => prompt::default_template(config.body),
+        };
 
-impl AiClient {
-    /// Create a new AI client
-    pub fn new(endpoint: String, api_key: String, model: String) -> Self {
-        Self {
-            endpoint,
+        Ok(Some(Self {
+            template,
+            temperature: config.temperature.unwrap_or(0.7),
+            max_tokens: config.max_tokens
+                .unwrap_or(if config.body { 300 } else { 100 }),
+            body: config.body,
+            subject_length: config.subject_length
+                .unwrap_or(prompt::DEFAULT_SUBJECT_LENGTH),
+            endpoint: config.endpoint.clone()
+                .unwrap_or_else(|| provider.default_endpoint().to_string()),
+            model: config.model.clone()
+                .unwrap_or_else(|| provider.default_model().to_string()),
+            timeout: config.timeout.map(Duration::from_secs)
+                .unwrap_or_else(|| provider.default_timeout()),
             api_key,
-            model,
-        }
+            provider,
+        }))
     }
 
-    /// Generate a commit message based on git diff
-    pub async fn generate_commit_message(&self, diff: &str) -> Result<String> {
-        if diff.trim().is_empty() {
+    /// Provider name and model, for progress output
+    pub fn describe(&self) -> String {
+        format!("{} ({})", self.provider.name(), self.model)
+    }
+
+    /// Generate a commit message for the summarized changes of a vault
+    pub async fn generate_commit_message(&self, summary: &Summary, vault_name: &str) -> Result<String> {
+        if summary.files.trim().is_empty() {
             bail!("No changes to generate commit message for");
         }
 
-        // Prepare the prompt
-        let prompt = format!(
-            "You are a helpful assistant that generates concise git commit messages based on code changes.\n\
-            \n\
-            Generate a commit message for the following git diff. The message should:\n\
-            - Be concise (1-2 lines maximum)\n\
-            - Start with a verb in present tense (e.g., 'add', 'fix', 'update', 'remove')\n\
-            - Describe WHAT changed, not HOW it changed\n\
-            - Not include any prefixes like 'feat:', 'fix:', etc.\n\
-            - Not include markdown formatting\n\
-            \n\
-            Git diff:\n\
-            ```\n\
-            {}\n\
-            ```\n\
-            \n\
-            Respond with ONLY the commit message, nothing else.",
-            // Truncate diff if too long (to avoid token limits)
-            if diff.len() > 4000 {
-                &diff[..4000]
-            } else {
-                diff
-            }
-        );
-
-        // Create request
-        let request = ChatRequest {
-            model: self.model.clone(),
-            messages: vec![ChatMessage {
-                role: "user".to_string(),
-                content: prompt,
-            }],
-            temperature: 0.7,
-            max_tokens: 100,
+        // Only look up the host name when the template asks for it
+        let host = if self.template.contains("{host}") { prompt::host_name() } else { String::new() };
+        let prompt = prompt::render(&self.template, &PromptVars {
+            diff: &summary.diff,
+            files: &summary.files,
+            host: &host,
+            vault: vault_name,
+            max_subject: self.subject_length,
+        });
+
+        let completion = Completion {
+            model: &self.model,
+            prompt: &prompt,
+            temperature: self.temperature,
+            max_tokens: self.max_tokens,
         };
 
         // Send request
-        let client = reqwest::Client::new();
-        let response = client
-            .post(&self.endpoint)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .json(&request)
+        let client = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .build()
+            .context("Failed to create HTTP client")?;
+        let request = self.provider.build_request(
+            client.post(&self.endpoint),
+            self.api_key.as_deref(),
+            &completion,
+        );
+        let response = request
             .send()
             .await
-            .context("Failed to send request to AI endpoint")?;
+            .with_context(|| format!("Failed to send request to {}", self.endpoint))?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -107,20 +124,66 @@ impl AiClient {
             bail!("AI request failed with status {}: {}", status, error_text);
         }
 
-        let chat_response: ChatResponse = response
+        let body: serde_json::Value = response
             .json()
             .await
             .context("Failed to parse AI response")?;
 
-        let message = chat_response
-            .choices
-            .first()
-            .context("No response from AI")?
-            .message
-            .content
-            .trim()
-            .to_string();
+        let response = self.provider.parse_response(&body)?;
+        prompt::clean_message(&response, self.body, self.subject_length)
+    }
+}
 
-        Ok(message)
+/// Find the API key: `ai.api_key` (config or credentials file), then the
+/// variable named by `ai.api_key_env`, then the output of `ai.api_key_cmd`
+fn resolve_api_key(config: &AiConfig) -> Result<Option<String>> {
+    if let Some(ref key) = config.api_key {
+        return Ok(Some(key.clone()));
     }
+
+    if let Some(ref var) = config.api_key_env {
+        match std::env::var(var) {
+            Ok(key) if !key.trim().is_empty() => return Ok(Some(key.trim().to_string())),
+            _ => bail!("ai.api_key_env is set, but ${} is empty or not set", var),
+        }
+    }
+
+    if let Some(ref command) = config.api_key_cmd {
+        return run_api_key_cmd(command).map(Some);
+    }
+
+    Ok(None)
+}
+
+/// Run `ai.api_key_cmd` through the shell and use the first line of its output
+fn run_api_key_cmd(command: &str) -> Result<String> {
+    let mut cache = API_KEY_CMD_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((cached_command, key)) = cache.as_ref() {
+        if cached_command == command {
+            return Ok(key.clone());
+        }
+    }
+
+    let output = if cfg!(windows) {
+        Command::new("cmd").args(["/C", command]).output()
+    } else {
+        Command::new("sh").args(["-c", command]).output()
+    }
+    .with_context(|| format!("Failed to run ai.api_key_cmd '{}'", command))?;
+
+    if !output.status.success() {
+        // Never echo stdout here; it may hold part of the key
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("ai.api_key_cmd '{}' failed ({}): {}", command, output.status, stderr.trim());
+    }
+
+    let stdout = String::from_utf8(output.stdout)
+        .context("ai.api_key_cmd printed invalid UTF-8")?;
+    let key = stdout.lines().next().unwrap_or_default().trim().to_string();
+    if key.is_empty() {
+        bail!("ai.api_key_cmd '{}' printed nothing", command);
+    }
+
+    *cache = Some((command.to_string(), key.clone()));
+    Ok(key)
 }
@@ -0,0 +1,149 @@
+// Prompt templates - builds the commit message prompt and cleans up the answer
+
+use anyhow::{bail, Context, Result};
+use std::path::{Component, Path};
+
+/// Default maximum length of the subject line
+pub const DEFAULT_SUBJECT_LENGTH: usize = 72;
+
+/// Values substituted into a prompt template
+pub struct PromptVars<'a> {
+    /// Diff excerpts (`{diff}`)
+    pub diff: &'a str,
+    /// One line per changed file (`{files}`)
+    pub files: &'a str,
+    /// Machine name (`{host}`)
+    pub host: &'a str,
+    /// Vault name (`{vault}`)
+    pub vault: &'a str,
+    /// Subject line limit (`{max_subject}`)
+    pub max_subject: usize,
+}
+
+/// Built-in prompt, asking for a subject line only or a subject and bullet-point body
+pub fn default_template(body: bool) -> String {
+    let format_rules = if body {
+        "- Start with a summary line of at most {max_subject} characters\n\
+         - Follow it with a blank line and 2-5 bullet points ('- ') describing the changes"
+    } else {
+        "- Be a single line of at most {max_subject} characters"
+    };
+
+    format!(
+        "You are a helpful assistant that generates concise git commit messages based on code changes.\n\
+        \n\
+        Generate a commit message for the following changes to the '{{vault}}' vault. The message should:\n\
+        {}\n\
+        - Start with a verb in present tense (e.g., 'add', 'fix', 'update', 'remove')\n\
+        - Describe WHAT changed, not HOW it changed\n\
+        - Not include any prefixes like 'feat:', 'fix:', etc.\n\
+        - Not include markdown formatting\n\
+        \n\
+        Changed files:\n\
+        {{files}}\n\
+        \n\
+        Diff excerpts:\n\
+        ```\n\
+        {{diff}}\n\
+        ```\n\
+        \n\
+        Respond with ONLY the commit message, nothing else.",
+        format_rules
+    )
+}
+
+/// Read `ai.prompt_file`. Relative paths are resolved against the vault repo,
+/// so a team can commit its prompt next to `.gfv.toml`.
+pub fn load_template(prompt_file: &str, repo_path: &Path) -> Result<String> {
+    let path = Path::new(prompt_file);
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else if let Some(rest) = prompt_file.strip_prefix("~/") {
+        dirs::home_dir().context("Failed to get home directory")?.join(rest)
+    } else {
+        if path.components().any(|c| c == Component::ParentDir) {
+            bail!("ai.prompt_file must not leave the vault repo: {}", prompt_file);
+        }
+        repo_path.join(path)
+    };
+
+    let template = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read ai.prompt_file {}", path.display()))?;
+    if !template.contains("{diff}") && !template.contains("{files}") {
+        bail!("ai.prompt_file {} must contain {{diff}} or {{files}}", path.display());
+    }
+    Ok(template)
+}
+
+/// Substitute the placeholders in a template
+pub fn render(template: &str, vars: &PromptVars) -> String {
+    template
+        .replace("{max_subject}", &vars.max_subject.to_string())
+        .replace("{vault}", vars.vault)
+        .replace("{host}", vars.host)
+        .replace("{files}", vars.files)
+        // Last, so placeholder-like text inside the diff is left alone
+        .replace("{diff}", vars.diff)
+}
+
+/// Name of this machine, for `{host}`
+pub fn host_name() -> String {
+    let from_env = std::env::var("HOSTNAME").or_else(|_| std::env::var("COMPUTERNAME")).ok();
+    let from_command = || {
+        std::process::Command::new("hostname").output().ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    from_env.or_else(from_command)
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "localhost".to_string())
+}
+
+/// Turn a model response into a commit message: drop code fences and
+/// quotes, limit the subject length and keep the body only if asked for
+pub fn clean_message(raw: &str, body: bool, max_subject: usize) -> Result<String> {
+    let lines: Vec<&str> = raw.lines()
+        .filter(|line| !line.trim_start().starts_with("```"))
+        .collect();
+
+    let Some(start) = lines.iter().position(|line| !line.trim().is_empty()) else {
+        bail!("AI returned an empty commit message");
+    };
+
+    let subject = lines[start].trim().trim_matches(['"', '\'', '`']).trim();
+    let subject = subject.strip_prefix("Commit message:").unwrap_or(subject).trim();
+    if subject.is_empty() {
+        bail!("AI returned an empty commit message");
+    }
+    let mut message = shorten_subject(subject, max_subject);
+
+    if body {
+        let rest: Vec<&str> = lines[start + 1..].iter()
+            .map(|line| line.trim_end())
+            .skip_while(|line| line.is_empty())
+            .collect();
+        let rest = rest.join("\n");
+        let rest = rest.trim_end();
+        if !rest.is_empty() {
+            message.push_str("\n\n");
+            message.push_str(rest);
+        }
+    }
+
+    Ok(message)
+}
+
+/// Cut a subject line to `max` characters at a word boundary
+fn shorten_subject(subject: &str, max: usize) -> String {
+    if subject.chars().count() <= max {
+        return subject.to_string();
+    }
+
+    let limit = max.saturating_sub(3);
+    let cut: String = subject.chars().take(limit).collect();
+    let cut = match cut.rfind(' ') {
+        Some(space) if space > limit / 2 => &cut[..space],
+        _ => cut.as_str(),
+    };
+    format!("{}...", cut.trim_end_matches([' ', ',', ';', ':']))
+}
@@ -0,0 +1,348 @@
+// AI providers - request/response shapes of each supported API
+
+use anyhow::{bail, Context, Result};
+use reqwest::RequestBuilder;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// A completion request, independent of the provider
+pub struct Completion<'a> {
+    pub model: &'a str,
+    pub prompt: &'a str,
+    pub temperature: f32,
+    pub max_tokens: u32,
+}
+
+/// An AI API that can turn a prompt into text
+pub trait Provider {
+    /// Name used in `ai.provider`
+    fn name(&self) -> &'static str;
+
+    fn default_endpoint(&self) -> &'static str;
+
+    fn default_model(&self) -> &'static str;
+
+    fn default_timeout(&self) -> Duration {
+        Duration::from_secs(30)
+    }
+
+    fn requires_api_key(&self) -> bool {
+        true
+    }
+
+    /// Add authentication headers and the JSON body to a POST request
+    fn build_request(&self, request: RequestBuilder, api_key: Option<&str>, completion: &Completion) -> RequestBuilder;
+
+    /// Extract the generated text from a response body
+    fn parse_response(&self, body: &Value) -> Result<String>;
+}
+
+/// Look up a provider by its `ai.provider` name (default: openai)
+pub fn by_name(name: Option<&str>) -> Result<Box<dyn Provider>> {
+    match name.unwrap_or("openai") {
+        "openai" => Ok(Box::new(OpenAi)),
+        "anthropic" => Ok(Box::new(Anthropic)),
+        "ollama" => Ok(Box::new(Ollama)),
+        other => bail!("Unknown AI provider '{}' (expected one of: {})", other, NAMES.join(", ")),
+    }
+}
+
+/// Valid values for `ai.provider`
+const NAMES: &[&str] = &["openai", "anthropic", "ollama"];
+
+/// OpenAI chat completions, and any API compatible with it
+pub struct OpenAi;
+
+impl Provider for OpenAi {
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+
+    fn default_endpoint(&self) -> &'static str {
+        "https://api.openai.com/v1/chat/completions"
+    }
+
+    fn default_model(&self) -> &'static str {
+        "gpt-4o-mini"
+    }
+
+    fn build_request(&self, request: RequestBuilder, api_key: Option<&str>, completion: &Completion) -> RequestBuilder {
+        let request = match api_key {
+            Some(key) => request.bearer_auth(key),
+            None => request,
+        };
+        request.json(&json!({
+            "model": completion.model,
+            "messages": [{ "role": "user", "content": completion.prompt }],
+            "temperature": completion.temperature,
+            "max_tokens": completion.max_tokens,
+        }))
+    }
+
+    fn parse_response(&self, body: &Value) -> Result<String> {
+        body.pointer("/choices/0/message/content")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .context("No response from AI")
+    }
+}
+
+/// Anthropic Messages API
+pub struct Anthropic;
+
+/// Value of the `anthropic-version` header
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+impl Provider for Anthropic {
+    fn name(&self) -> &'static str {
+        "anthropic"
+    }
+
+    fn default_endpoint(&self) -> &'static str {
+        "https://api.anthropic.com/v1/messages"
+    }
+
+    fn default_model(&self) -> &'static str {
+        "claude-3-5-haiku-latest"
+    }
+
+    fn build_request(&self, request: RequestBuilder, api_key: Option<&str>, completion: &Completion) -> RequestBuilder {
+        let request = match api_key {
+            Some(key) => request.header("x-api-key", key),
+            None => request,
+        };
+        request
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .json(&json!({
+                "model": completion.model,
+                "max_tokens": completion.max_tokens,
+                "temperature": completion.temperature,
+                "messages": [{ "role": "user", "content": completion.prompt }],
+            }))
+    }
+
+    fn parse_response(&self, body: &Value) -> Result<String> {
+        // Content is a list of blocks; join the text blocks
+        let blocks = body.get("content")
+            .and_then(Value::as_array)
+            .context("No response from AI")?;
+        let text: String = blocks.iter()
+            .filter(|block| block.get("type").and_then(Value::as_str) == Some("text"))
+            .filter_map(|block| block.get("text").and_then(Value::as_str))
+            .collect();
+        if text.is_empty() {
+            bail!("No response from AI");
+        }
+        Ok(text)
+    }
+}
+
+/// Local Ollama server (no API key)
+pub struct Ollama;
+
+impl Provider for Ollama {
+    fn name(&self) -> &'static str {
+        "ollama"
+    }
+
+    fn default_endpoint(&self) -> &'static str {
+        "http://localhost:11434/api/chat"
+    }
+
+    fn default_model(&self) -> &'static str {
+        "llama3.2"
+    }
+
+    // Local models can take a while to load on first use
+    fn default_timeout(&self) -> Duration {
+        Duration::from_secs(120)
+    }
+
+    fn requires_api_key(&self) -> bool {
+        false
+    }
+
+    fn build_request(&self, request: RequestBuilder, api_key: Option<&str>, completion: &Completion) -> RequestBuilder {
+        // Ollama behind an authenticating proxy
+        let request = match api_key {
+            Some(key) => request.bearer_auth(key),
+            None => request,
+        };
+        request.json(&json!({
+            "model": completion.model,
+            "messages": [{ "role": "user", "content": completion.prompt }],
+            "stream": false,
+            "options": {
+                "temperature": completion.temperature,
+                "num_predict": completion.max_tokens,
+            },
+        }))
+    }
+
+    fn parse_response(&self, body: &Value) -> Result<String> {
+        body.pointer("/message/content")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .context("No response from AI")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::summarize::Summary;
+    use crate::ai::AiClient;
+    use crate::config::AiConfig;
+    use std::collections::HashMap;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::path::Path;
+    use std::thread::JoinHandle;
+
+    /// What the mock server received
+    struct Received {
+        /// Request line, e.g. `POST /v1/messages HTTP/1.1`
+        request_line: String,
+        /// Header names in lowercase
+        headers: HashMap<String, String>,
+        body: Value,
+    }
+
+    /// Serve a single request on a local port, answering with `status` and `response`.
+    /// Returns the base URL and a handle yielding the request.
+    fn serve_once(status: u16, response: Value) -> (String, JoinHandle<Received>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut headers = HashMap::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let Some((name, value)) = line.trim_end().split_once(':') else { break };
+                headers.insert(name.to_lowercase(), value.trim().to_string());
+            }
+            let length: usize = headers["content-length"].parse().unwrap();
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+
+            let response = response.to_string();
+            write!(&stream, "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status, response.len(), response).unwrap();
+
+            Received {
+                request_line: request_line.trim_end().to_string(),
+                headers,
+                body: serde_json::from_slice(&body).unwrap(),
+            }
+        });
+        (url, handle)
+    }
+
+    fn summary() -> Summary {
+        Summary {
+            files: "M zsh/zshrc (+1 -1)".to_string(),
+            diff: "-export EDITOR=vim\n+export EDITOR=nvim".to_string(),
+        }
+    }
+
+    /// Generate a message through a client for `provider` pointed at `endpoint`
+    fn generate(provider: &str, endpoint: String, api_key: Option<&str>) -> Result<String> {
+        let config = AiConfig {
+            provider: Some(provider.to_string()),
+            api_key: api_key.map(str::to_string),
+            model: Some("test-model".to_string()),
+            endpoint: Some(endpoint),
+            temperature: Some(0.5),
+            max_tokens: Some(64),
+            ..Default::default()
+        };
+        let client = AiClient::from_config(&config, Path::new(".")).unwrap().unwrap();
+        tokio::runtime::Runtime::new().unwrap()
+            .block_on(client.generate_commit_message(&summary(), "default"))
+    }
+
+    /// The prompt sent as the single user message
+    fn prompt(body: &Value) -> &str {
+        assert_eq!(body["messages"][0]["role"], "user");
+        body["messages"][0]["content"].as_str().unwrap()
+    }
+
+    #[test]
+    fn openai_request_and_response() {
+        let (url, server) = serve_once(200, json!({
+            "choices": [{ "message": { "role": "assistant", "content": "Switch editor to nvim" } }],
+        }));
+
+        let message = generate("openai", format!("{}/v1/chat/completions", url), Some("sk-test")).unwrap();
+        let received = server.join().unwrap();
+
+        assert_eq!(message, "Switch editor to nvim");
+        assert_eq!(received.request_line, "POST /v1/chat/completions HTTP/1.1");
+        assert_eq!(received.headers["authorization"], "Bearer sk-test");
+        assert_eq!(received.body["model"], "test-model");
+        assert_eq!(received.body["temperature"], 0.5);
+        assert_eq!(received.body["max_tokens"], 64);
+        assert!(prompt(&received.body).contains("+export EDITOR=nvim"));
+    }
+
+    #[test]
+    fn anthropic_request_and_response() {
+        let (url, server) = serve_once(200, json!({
+            "type": "message",
+            "content": [
+                { "type": "text", "text": "Switch editor " },
+                { "type": "tool_use", "id": "ignored" },
+                { "type": "text", "text": "to nvim" },
+            ],
+        }));
+
+        let message = generate("anthropic", format!("{}/v1/messages", url), Some("sk-ant-test")).unwrap();
+        let received = server.join().unwrap();
+
+        assert_eq!(message, "Switch editor to nvim");
+        assert_eq!(received.request_line, "POST /v1/messages HTTP/1.1");
+        assert_eq!(received.headers["x-api-key"], "sk-ant-test");
+        assert_eq!(received.headers["anthropic-version"], ANTHROPIC_VERSION);
+        assert!(!received.headers.contains_key("authorization"));
+        assert_eq!(received.body["model"], "test-model");
+        assert_eq!(received.body["max_tokens"], 64);
+        assert!(prompt(&received.body).contains("zsh/zshrc"));
+    }
+
+    #[test]
+    fn ollama_request_and_response() {
+        let (url, server) = serve_once(200, json!({
+            "message": { "role": "assistant", "content": "Switch editor to nvim" },
+            "done": true,
+        }));
+
+        let message = generate("ollama", format!("{}/api/chat", url), None).unwrap();
+        let received = server.join().unwrap();
+
+        assert_eq!(message, "Switch editor to nvim");
+        assert_eq!(received.request_line, "POST /api/chat HTTP/1.1");
+        assert!(!received.headers.contains_key("authorization"));
+        assert_eq!(received.body["stream"], false);
+        assert_eq!(received.body["options"]["temperature"], 0.5);
+        assert_eq!(received.body["options"]["num_predict"], 64);
+        assert!(prompt(&received.body).contains("zsh/zshrc"));
+    }
+
+    #[test]
+    fn error_status_is_reported() {
+        let (url, server) = serve_once(401, json!({ "error": { "message": "invalid api key" } }));
+
+        let error = generate("openai", url, Some("sk-wrong")).unwrap_err();
+        server.join().unwrap();
+
+        let error = format!("{:#}", error);
+        assert!(error.contains("401"), "{}", error);
+        assert!(error.contains("invalid api key"), "{}", error);
+    }
+}
@@ -0,0 +1,141 @@
+// Diff summarizer - fits every changed file into the AI prompt budget
+
+use super::redact::RedactedFile;
+
+/// Default prompt budget for the diff summary, in tokens
+pub const DEFAULT_TOKEN_BUDGET: usize = 3000;
+
+/// Rough size of a token in characters, good enough for budgeting
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Lines longer than this (minified files, lock files) are cut
+const MAX_LINE_CHARS: usize = 200;
+
+/// Smallest useful excerpt of a patch; below this only the stat line is sent
+const MIN_PATCH_CHARS: usize = 80;
+
+/// Changes as sent to the AI
+pub struct Summary {
+    /// A stat line for every changed file
+    pub files: String,
+    /// Patch excerpts
+    pub diff: String,
+}
+
+/// Build the diff summary sent to the AI: a stat line for every changed file,
+/// and as much of each patch as the budget allows, shared fairly between
+/// files so that one large change cannot crowd out the others.
+pub fn summarize(files: &[RedactedFile], token_budget: usize) -> Summary {
+    let stats = stat_list(files);
+    let budget = token_budget.saturating_mul(CHARS_PER_TOKEN);
+    let mut remaining = budget.saturating_sub(stats.len());
+
+    // Patches worth including, smallest first so leftover budget flows to larger ones
+    let mut candidates: Vec<(usize, String)> = files.iter()
+        .enumerate()
+        .filter(|(_, file)| !file.diff.binary)
+        .filter_map(|(i, file)| file.patch.as_deref().map(|patch| (i, collapse_long_lines(patch))))
+        .filter(|(_, patch)| !patch.trim().is_empty())
+        .collect();
+    candidates.sort_by_key(|(_, patch)| patch.len());
+
+    let mut excerpts: Vec<Option<String>> = vec![None; files.len()];
+    let count = candidates.len();
+    for (n, (i, patch)) in candidates.into_iter().enumerate() {
+        let share = remaining / (count - n);
+        if share < MIN_PATCH_CHARS {
+            continue;
+        }
+        let excerpt = truncate_patch(&patch, share);
+        remaining -= excerpt.len().min(remaining);
+        excerpts[i] = Some(excerpt);
+    }
+
+    let mut diff = String::new();
+    let mut omitted = 0;
+    for (file, excerpt) in files.iter().zip(excerpts) {
+        match excerpt {
+            Some(excerpt) => {
+                if !diff.is_empty() {
+                    diff.push('\n');
+                }
+                diff.push_str(&excerpt);
+            }
+            None if file.patch.is_some() && !file.diff.binary => omitted += 1,
+            None => {}
+        }
+    }
+    if omitted > 0 {
+        diff.push_str(&format!("\n({} more {} omitted to fit the budget; see the list of changed files)\n",
+            omitted, if omitted == 1 { "diff" } else { "diffs" }));
+    }
+
+    Summary {
+        files: stats.trim_end().to_string(),
+        diff: diff.trim_end().to_string(),
+    }
+}
+
+/// One line per changed file: status, path, line counts and markers
+fn stat_list(files: &[RedactedFile]) -> String {
+    let mut list = String::new();
+    for file in files {
+        let diff = file.diff;
+        let path = match diff.old_path {
+            Some(ref old) => format!("{} -> {}", old, diff.path),
+            None => diff.path.clone(),
+        };
+
+        let mut notes = Vec::new();
+        if diff.binary {
+            notes.push("binary".to_string());
+        } else {
+            notes.push(format!("+{} -{}", diff.added, diff.removed));
+        }
+        if file.patch.is_none() {
+            notes.push("content withheld".to_string());
+        }
+
+        list.push_str(&format!("{} {} ({})\n", diff.status, path, notes.join(", ")));
+    }
+    list
+}
+
+/// Cut very long lines so a single minified line cannot eat the budget
+fn collapse_long_lines(patch: &str) -> String {
+    let mut output = String::with_capacity(patch.len());
+    for line in patch.lines() {
+        if line.chars().count() > MAX_LINE_CHARS {
+            let cut: String = line.chars().take(MAX_LINE_CHARS).collect();
+            output.push_str(&cut);
+            output.push_str(" [line truncated]");
+        } else {
+            output.push_str(line);
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// Keep whole lines of `patch` up to `max_chars`, noting how many were dropped
+fn truncate_patch(patch: &str, max_chars: usize) -> String {
+    if patch.len() <= max_chars {
+        return patch.to_string();
+    }
+
+    let total_lines = patch.lines().count();
+    let mut output = String::new();
+    let mut kept = 0;
+    // Leave room for the omission note
+    let limit = max_chars.saturating_sub(40);
+    for line in patch.lines() {
+        if output.len() + line.len() + 1 > limit {
+            break;
+        }
+        output.push_str(line);
+        output.push('\n');
+        kept += 1;
+    }
+    output.push_str(&format!("[... {} more lines]\n", total_lines - kept));
+    output
+}
@@ -0,0 +1,139 @@
+// Catalog module - well-known application settings and where they live
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Built-in catalog, see catalog.toml
+const CATALOG_TOML: &str = include_str!("catalog.toml");
+
+#[derive(Debug, Deserialize)]
+struct CatalogFile {
+    app: Vec<App>,
+}
+
+/// An application and the settings files worth keeping in a vault
+#[derive(Debug, Deserialize)]
+pub struct App {
+    /// Short name used on the command line (`gfv scan --link zsh`)
+    pub id: String,
+    pub name: String,
+    /// OS the app only exists on; entries are linked with this platform restriction
+    pub platform: Option<String>,
+    pub files: Vec<AppFile>,
+}
+
+/// A settings file (or directory) of an app
+#[derive(Debug, Deserialize)]
+pub struct AppFile {
+    /// Canonical vault path
+    pub vault_path: String,
+    /// Locations on every OS
+    #[serde(default)]
+    paths: Vec<String>,
+    #[serde(default)]
+    macos: Vec<String>,
+    #[serde(default)]
+    linux: Vec<String>,
+    #[serde(default)]
+    windows: Vec<String>,
+}
+
+impl AppFile {
+    /// Locations to look at on `os`, most specific first
+    fn locations(&self, os: &str) -> impl Iterator<Item = &str> {
+        let specific = match os {
+            "macos" => &self.macos,
+            "linux" => &self.linux,
+            "windows" => &self.windows,
+            _ => &self.paths,
+        };
+        specific.iter().chain(&self.paths).map(String::as_str)
+    }
+
+    /// Locations on any OS
+    fn all_locations(&self) -> impl Iterator<Item = &str> {
+        [&self.paths, &self.macos, &self.linux, &self.windows]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+    }
+}
+
+/// A catalog file found on this machine
+pub struct Found {
+    pub app: &'static App,
+    pub file: &'static AppFile,
+    pub source_path: PathBuf,
+}
+
+/// All catalog apps
+pub fn apps() -> &'static [App] {
+    static CATALOG: OnceLock<Vec<App>> = OnceLock::new();
+    CATALOG.get_or_init(|| {
+        let catalog: CatalogFile = toml::from_str(CATALOG_TOML)
+            .expect("built-in catalog.toml is valid");
+        catalog.app
+    })
+}
+
+/// Name of this OS as used by `platform` (macos, linux, windows)
+pub fn current_os() -> &'static str {
+    std::env::consts::OS
+}
+
+/// Expand a catalog location (`~/...`) to a path under `home`
+fn expand(location: &str, home: &Path) -> PathBuf {
+    let relative = location.strip_prefix("~/").unwrap_or(location);
+    home.join(relative)
+}
+
+/// Canonical vault path for a source path that is a known location on any OS
+pub fn vault_path_for(source_path: &Path) -> Option<&'static str> {
+    let home = dirs::home_dir()?;
+    apps().iter()
+        .flat_map(|app| &app.files)
+        .find(|file| file.all_locations().any(|location| expand(location, &home) == source_path))
+        .map(|file| file.vault_path.as_str())
+}
+
+/// App and source path of the catalog file stored at `vault_path`: the first
+/// location on this OS that exists, else the first one that would be used
+pub fn source_for(vault_path: &str) -> Option<(&'static App, PathBuf)> {
+    let home = dirs::home_dir()?;
+    let os = current_os();
+    apps().iter()
+        .flat_map(|app| app.files.iter().map(move |file| (app, file)))
+        .find(|(_, file)| file.vault_path == vault_path)
+        .and_then(|(app, file)| {
+            let source_path = file.locations(os)
+                .map(|location| expand(location, &home))
+                .find(|path| path.exists())
+                .or_else(|| file.locations(os).chain(file.all_locations()).next().map(|location| expand(location, &home)))?;
+            Some((app, source_path))
+        })
+}
+
+/// Catalog files that exist on this machine (first existing location of each file)
+pub fn scan() -> Result<Vec<Found>> {
+    let home = dirs::home_dir()
+        .context("Failed to get home directory")?;
+    let os = current_os();
+
+    let mut found = Vec::new();
+    for app in apps() {
+        if app.platform.as_deref().is_some_and(|platform| platform != os) {
+            continue;
+        }
+        for file in &app.files {
+            let existing = file.locations(os)
+                .map(|location| expand(location, &home))
+                .find(|path| path.exists());
+            if let Some(source_path) = existing {
+                found.push(Found { app, file, source_path });
+            }
+        }
+    }
+    Ok(found)
+}
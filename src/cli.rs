@@ -1,6 +1,8 @@
 use clap::{Parser, Subcommand};
-use anyhow::Result;
+use std::path::PathBuf;
+use anyhow::{bail, Result};
 use crate::commands;
+use crate::output::OutputFormat;
 
 // Use version set by build.rs
 const VERSION: &str = env!("GFV_VERSION");
@@ -9,6 +11,15 @@ const VERSION: &str = env!("GFV_VERSION");
 #[command(name = "gfv")]
 #[command(version = VERSION, about = "Git-based file version management tool", long_about = None)]
 pub struct Cli {
+    /// Output format for read commands (list, status, scan, vault list/info, config --list, alias list, debug paths)
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    // Applied in main() before parsing, so aliases are loaded from the same file
+    /// Use this config file instead of <gfv home>/config.toml (gfv home: $GFV_HOME or ~/.gfv)
+    #[arg(long, global = true, value_name = "FILE")]
+    config: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -17,7 +28,7 @@ pub struct Cli {
 enum Commands {
     /// Initialize a new vault
     Init {
-        /// Vault path (default: ~/.gfv)
+        /// Vault path (default: <gfv home>/<name>)
         path: Option<String>,
 
         /// Remote repository URL
@@ -32,6 +43,14 @@ enum Commands {
         #[arg(short, long, default_value = "default")]
         name: String,
 
+        /// SSH private key for the remote (default: agent, ~/.ssh/config, id_ed25519/id_ecdsa/id_rsa)
+        #[arg(long)]
+        ssh_key: Option<String>,
+
+        /// Environment variable holding an HTTPS access token for the remote
+        #[arg(long)]
+        token_env: Option<String>,
+
         /// Don't sync files after cloning from remote
         #[arg(long)]
         no_sync: bool,
@@ -39,10 +58,11 @@ enum Commands {
 
     /// Link a file to vault
     Link {
-        /// Source file path
-        source: String,
+        /// Source paths; globs such as '~/.config/{git,tmux}/*' are expanded by gfv
+        #[arg(required = true)]
+        sources: Vec<String>,
 
-        /// Custom name in vault
+        /// Custom name in vault (single path only)
         #[arg(short, long)]
         name: Option<String>,
 
@@ -50,11 +70,146 @@ enum Commands {
         #[arg(short, long)]
         platform: Option<String>,
 
+        /// Link without asking for confirmation (several paths, or a sensitive file)
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Vault name to use
+        #[arg(long)]
+        vault: Option<String>,
+    },
+
+    /// Find well-known settings files on this machine that aren't managed yet
+    Scan {
+        /// Link these apps from the catalog (e.g. zsh git neovim), or 'all'
+        #[arg(long, num_args = 1.., value_name = "APP")]
+        link: Vec<String>,
+
+        /// Also show files that are already managed
+        #[arg(long)]
+        all: bool,
+
+        /// Link several files without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Vault name to use
+        #[arg(long)]
+        vault: Option<String>,
+    },
+
+    /// Import dotfiles managed with stow, chezmoi, yadm or a bare git repository
+    Import {
+        /// Tool the dotfiles are managed with
+        #[arg(long, value_enum)]
+        from: commands::import::ImportFrom,
+
+        /// Stow directory, bare repository or chezmoi source directory (default for chezmoi and yadm: their usual location)
+        path: Option<String>,
+
+        /// Directory the files are deployed to (default: parent of the stow directory, core.worktree or home)
+        #[arg(long)]
+        target: Option<String>,
+
+        /// Import the current files of a bare repository without its history
+        #[arg(long)]
+        no_history: bool,
+
+        /// Show what would be imported without changing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Import without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Vault name to use
+        #[arg(long)]
+        vault: Option<String>,
+    },
+
+    /// Manage files that are in the vault repo but not linked on this machine (e.g. after cloning)
+    Adopt {
+        /// Entries to adopt: numbers or ranges from the list, vault paths or globs, or 'all'
+        select: Vec<String>,
+
+        /// Adopt without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Vault name to use
+        #[arg(long)]
+        vault: Option<String>,
+    },
+
+    /// Pack managed files and an install.sh into a bundle for machines without gfv
+    Export {
+        /// Entries to export: vault paths, globs or source paths (default: all)
+        paths: Vec<String>,
+
+        /// Write a .tar.gz bundle to this file
+        #[arg(long, value_name = "FILE")]
+        bundle: String,
+
+        /// Platform to export for (macos, linux; default: this machine's)
+        #[arg(short, long)]
+        platform: Option<String>,
+
         /// Vault name to use
         #[arg(long)]
         vault: Option<String>,
     },
 
+    /// Sync through bundle files, for machines without access to a remote
+    Bundle {
+        #[command(subcommand)]
+        command: BundleCommands,
+    },
+
+    /// Change the vault path of a managed file, keeping its history
+    Mv {
+        /// Current vault path
+        old: String,
+
+        /// New vault path
+        new: String,
+
+        /// Vault name to use
+        #[arg(long)]
+        vault: Option<String>,
+    },
+
+    /// Change where a managed file lives on disk
+    Relink {
+        /// Vault file path
+        file: String,
+
+        /// New source path
+        source: String,
+
+        /// Vault name to use
+        #[arg(long)]
+        vault: Option<String>,
+    },
+
+    /// Move or copy a managed file to another vault
+    Transfer {
+        /// Vault file path
+        file: String,
+
+        /// Vault to take it from (default: active vault)
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Vault to put it in
+        #[arg(long)]
+        to: String,
+
+        /// Keep it in the source vault as well
+        #[arg(long)]
+        copy: bool,
+    },
+
     /// Unlink a file from vault
     Unlink {
         /// Vault file path
@@ -82,6 +237,10 @@ enum Commands {
 
     /// Show vault status
     Status {
+        /// Fetch from the primary remote and show ahead/behind and incoming changes
+        #[arg(long)]
+        remote: bool,
+
         /// Vault name to use
         #[arg(long)]
         vault: Option<String>,
@@ -89,10 +248,17 @@ enum Commands {
 
     /// Backup changes to vault (and remote if configured)
     Backup {
+        /// Only back up these entries (vault paths, globs such as 'nvim/*', or source paths)
+        paths: Vec<String>,
+
         /// Commit message
         #[arg(short, long)]
         message: Option<String>,
 
+        /// Create one commit per changed entry, each with its own message
+        #[arg(long, conflicts_with = "message")]
+        split: bool,
+
         /// Force push
         #[arg(short, long)]
         force: bool,
@@ -101,6 +267,10 @@ enum Commands {
         #[arg(short = 'u', long)]
         set_upstream: bool,
 
+        /// Only sync with this remote (default: primary and all mirrors)
+        #[arg(long)]
+        remote: Option<String>,
+
         /// Vault name to use
         #[arg(long)]
         vault: Option<String>,
@@ -140,6 +310,41 @@ enum Commands {
         /// Unset a configuration key
         #[arg(long)]
         unset: Option<String>,
+
+        /// Show which layer (env, vault, global, default) each value comes from
+        #[arg(long)]
+        show_origin: bool,
+
+        /// Write to the vault's own settings instead of the global config
+        #[arg(long, conflicts_with_all = ["global", "vault_shared", "credentials"])]
+        local: bool,
+
+        /// Write to the shared settings committed in the vault repo (repo/.gfv.toml)
+        #[arg(long, conflicts_with_all = ["global", "credentials"])]
+        vault_shared: bool,
+
+        /// Write a secret (ai.api_key) to the credentials file, readable only by you
+        #[arg(long, conflicts_with = "global")]
+        credentials: bool,
+
+        /// Write to the global config (default)
+        #[arg(long)]
+        global: bool,
+
+        /// Vault name (default: active vault)
+        #[arg(long)]
+        vault: Option<String>,
+    },
+
+    /// Check vaults and config for broken state
+    Doctor {
+        /// Repair the problems that can be repaired safely
+        #[arg(long)]
+        fix: bool,
+
+        /// Only check this vault (default: all vaults)
+        #[arg(long)]
+        vault: Option<String>,
     },
 
     /// Manage command aliases
@@ -182,6 +387,37 @@ enum AliasCommands {
     List,
 }
 
+#[derive(Subcommand)]
+enum BundleCommands {
+    /// Write the vault history and file entries to a bundle file
+    Create {
+        /// Bundle file to write
+        file: String,
+
+        /// Only include commits after this one (the tip of the last applied bundle)
+        #[arg(long, value_name = "REF")]
+        since: Option<String>,
+
+        /// Vault name to use
+        #[arg(long)]
+        vault: Option<String>,
+    },
+
+    /// Bring in the commits and file entries of a bundle, then restore
+    Apply {
+        /// Bundle file to read
+        file: String,
+
+        /// Overwrite local changes without asking
+        #[arg(short, long)]
+        force: bool,
+
+        /// Vault name to use
+        #[arg(long)]
+        vault: Option<String>,
+    },
+}
+
 #[derive(Subcommand)]
 enum DebugCommands {
     /// Show gfv paths and status
@@ -205,7 +441,7 @@ enum VaultCommands {
         /// Vault name
         name: String,
 
-        /// Vault path (default: ~/.gfv/<name>)
+        /// Vault path (default: <gfv home>/<name>)
         path: Option<String>,
 
         /// Remote repository URL
@@ -215,6 +451,14 @@ enum VaultCommands {
         /// Branch name (defaults to remote default branch or config default)
         #[arg(short, long)]
         branch: Option<String>,
+
+        /// SSH private key for the remote (default: agent, ~/.ssh/config, id_ed25519/id_ecdsa/id_rsa)
+        #[arg(long)]
+        ssh_key: Option<String>,
+
+        /// Environment variable holding an HTTPS access token for the remote
+        #[arg(long)]
+        token_env: Option<String>,
     },
 
     /// Switch to a vault
@@ -233,6 +477,24 @@ enum VaultCommands {
         delete_files: bool,
     },
 
+    /// Rename a vault (keeps it active and updates aliases that name it)
+    Rename {
+        /// Current vault name
+        old: String,
+
+        /// New vault name
+        new: String,
+    },
+
+    /// Move a vault directory (with its repo and manifest) to another path
+    Move {
+        /// Vault name
+        name: String,
+
+        /// New vault directory (must not exist or be empty)
+        path: String,
+    },
+
     /// Show vault information
     Info {
         /// Vault name (default: active vault)
@@ -251,6 +513,14 @@ enum VaultCommands {
         /// Vault name (default: active vault)
         #[arg(short, long)]
         name: Option<String>,
+
+        /// SSH private key for the remote (default: agent, ~/.ssh/config, id_ed25519/id_ecdsa/id_rsa)
+        #[arg(long)]
+        ssh_key: Option<String>,
+
+        /// Environment variable holding an HTTPS access token for the remote
+        #[arg(long)]
+        token_env: Option<String>,
     },
 
     /// Set remote branch
@@ -269,50 +539,147 @@ enum VaultCommands {
         #[arg(short, long)]
         name: Option<String>,
     },
+
+    /// Manage named remotes (primary and mirrors)
+    Remote {
+        #[command(subcommand)]
+        command: RemoteCommands,
+    },
 }
 
-impl Cli {
-    pub fn parse() -> Self {
-        Parser::parse()
-    }
+#[derive(Subcommand)]
+enum RemoteCommands {
+    /// Add a named remote (mirrors receive every backup)
+    Add {
+        /// Remote name (e.g., nas, usb)
+        name: String,
+
+        /// Remote URL or path to a bare repository
+        url: String,
+
+        /// Branch name (default: current vault branch)
+        #[arg(short, long)]
+        branch: Option<String>,
+
+        /// Make this the primary remote (restore pulls from it)
+        #[arg(long)]
+        primary: bool,
+
+        /// SSH private key for this remote
+        #[arg(long)]
+        ssh_key: Option<String>,
 
+        /// Environment variable holding an HTTPS access token for this remote
+        #[arg(long)]
+        token_env: Option<String>,
+
+        /// Vault name (default: active vault)
+        #[arg(long)]
+        vault: Option<String>,
+    },
+
+    /// Remove a named remote
+    Remove {
+        /// Remote name
+        name: String,
+
+        /// Vault name (default: active vault)
+        #[arg(long)]
+        vault: Option<String>,
+    },
+
+    /// List remotes
+    List {
+        /// Vault name (default: active vault)
+        #[arg(long)]
+        vault: Option<String>,
+    },
+
+    /// Designate the primary remote
+    SetPrimary {
+        /// Remote name
+        name: String,
+
+        /// Vault name (default: active vault)
+        #[arg(long)]
+        vault: Option<String>,
+    },
+}
+
+impl Cli {
     pub fn execute(self) -> Result<()> {
+        let format = self.format;
+        if format.is_json() && !self.command.supports_json() {
+            bail!("--format json is not supported for this command");
+        }
+
         match self.command {
-            Commands::Init { path, remote, branch, name, no_sync } => {
-                commands::init(path, remote, branch, name, no_sync)
+            Commands::Init { path, remote, branch, name, ssh_key, token_env, no_sync } => {
+                commands::init(path, remote, branch, name, ssh_key, token_env, no_sync)
+            }
+            Commands::Link { sources, name, platform, yes, vault } => {
+                commands::link(sources, name, platform, yes, vault)
+            }
+            Commands::Scan { link, all, yes, vault } => {
+                commands::scan(link, all, yes, vault, format)
+            }
+            Commands::Import { from, path, target, no_history, dry_run, yes, vault } => {
+                commands::import(from, path, target, no_history, dry_run, yes, vault)
+            }
+            Commands::Adopt { select, yes, vault } => {
+                commands::adopt(select, yes, vault)
+            }
+            Commands::Export { paths, bundle, platform, vault } => {
+                commands::export(paths, bundle, platform, vault)
+            }
+            Commands::Bundle { command } => {
+                match command {
+                    BundleCommands::Create { file, since, vault } => {
+                        commands::bundle::create(file, since, vault)
+                    }
+                    BundleCommands::Apply { file, force, vault } => {
+                        commands::bundle::apply(file, force, vault)
+                    }
+                }
+            }
+            Commands::Mv { old, new, vault } => {
+                commands::mv(old, new, vault)
+            }
+            Commands::Relink { file, source, vault } => {
+                commands::relink(file, source, vault)
             }
-            Commands::Link { source, name, platform, vault } => {
-                commands::link(source, name, platform, vault)
+            Commands::Transfer { file, from, to, copy } => {
+                commands::transfer(file, from, to, copy)
             }
             Commands::Unlink { file, delete_files, vault } => {
                 commands::unlink(file, delete_files, vault)
             }
             Commands::List { long, vault } => {
-                commands::list(long, vault)
+                commands::list(long, vault, format)
             }
-            Commands::Status { vault } => {
-                commands::status(vault)
+            Commands::Status { remote, vault } => {
+                commands::status(vault, remote, format)
             }
-            Commands::Backup { message, force, set_upstream, vault } => {
-                // Need to use tokio runtime for async backup
-                #[cfg(feature = "ai")]
-                {
-                    tokio::runtime::Runtime::new()?.block_on(async {
-                        commands::backup(message, force, set_upstream, vault).await
-                    })
-                }
-                #[cfg(not(feature = "ai"))]
-                {
-                    tokio::runtime::Runtime::new()?.block_on(async {
-                        commands::backup(message, force, set_upstream, vault).await
-                    })
-                }
+            Commands::Backup { paths, message, split, force, set_upstream, remote, vault } => {
+                commands::backup(paths, message, split, force, set_upstream, remote, vault)
             }
             Commands::Restore { rebase, dry_run, force, vault } => {
                 commands::restore(rebase, dry_run, force, vault)
             }
-            Commands::Config { key, value, list, unset } => {
-                commands::config(key, value, list, unset)
+            Commands::Config { key, value, list, unset, show_origin, local, vault_shared, credentials, global: _, vault } => {
+                let scope = if local {
+                    commands::config::Scope::Local
+                } else if vault_shared {
+                    commands::config::Scope::Shared
+                } else if credentials {
+                    commands::config::Scope::Credentials
+                } else {
+                    commands::config::Scope::Global
+                };
+                commands::config(key, value, list, unset, show_origin, scope, vault, format)
+            }
+            Commands::Doctor { fix, vault } => {
+                commands::doctor(fix, vault)
             }
             Commands::Alias { command } => {
                 match command {
@@ -323,23 +690,25 @@ impl Cli {
                         commands::alias::remove(name)
                     }
                     AliasCommands::List => {
-                        commands::alias::list()
+                        commands::alias::list(format)
                     }
                 }
             }
             Commands::Vault { command } => {
                 match command {
-                    VaultCommands::List => commands::vault::list(),
-                    VaultCommands::Create { name, path, remote, branch } => {
-                        commands::vault::create(name, path, remote, branch)
+                    VaultCommands::List => commands::vault::list(format),
+                    VaultCommands::Create { name, path, remote, branch, ssh_key, token_env } => {
+                        commands::vault::create(name, path, remote, branch, ssh_key, token_env)
                     }
                     VaultCommands::Switch { name } => commands::vault::switch(name),
                     VaultCommands::Remove { name, delete_files } => {
                         commands::vault::remove(name, delete_files)
                     }
-                    VaultCommands::Info { name } => commands::vault::info(name),
-                    VaultCommands::SetRemote { url, branch, name } => {
-                        commands::vault::set_remote(url, branch, name)
+                    VaultCommands::Rename { old, new } => commands::vault::rename(old, new),
+                    VaultCommands::Move { name, path } => commands::vault::move_vault(name, path),
+                    VaultCommands::Info { name } => commands::vault::info(name, format),
+                    VaultCommands::SetRemote { url, branch, name, ssh_key, token_env } => {
+                        commands::vault::set_remote(url, branch, name, ssh_key, token_env)
                     }
                     VaultCommands::SetBranch { branch, name } => {
                         commands::vault::set_branch(branch, name)
@@ -347,14 +716,43 @@ impl Cli {
                     VaultCommands::RemoveRemote { name } => {
                         commands::vault::remove_remote(name)
                     }
+                    VaultCommands::Remote { command } => match command {
+                        RemoteCommands::Add { name, url, branch, primary, ssh_key, token_env, vault } => {
+                            commands::vault::remote_add(name, url, branch, primary, ssh_key, token_env, vault)
+                        }
+                        RemoteCommands::Remove { name, vault } => {
+                            commands::vault::remote_remove(name, vault)
+                        }
+                        RemoteCommands::List { vault } => commands::vault::remote_list(vault),
+                        RemoteCommands::SetPrimary { name, vault } => {
+                            commands::vault::remote_set_primary(name, vault)
+                        }
+                    },
                 }
             }
             Commands::Debug { command } => {
                 match command {
-                    DebugCommands::Paths => commands::debug::show_paths(),
+                    DebugCommands::Paths => commands::debug::show_paths(format),
                     DebugCommands::Clean { force } => commands::debug::clean(force),
                 }
             }
         }
     }
 }
+
+impl Commands {
+    /// Commands that can print their output as JSON
+    fn supports_json(&self) -> bool {
+        match self {
+            Commands::List { .. } | Commands::Status { .. } => true,
+            Commands::Config { list, .. } => *list,
+            Commands::Scan { link, .. } => link.is_empty(),
+            Commands::Alias { command } => matches!(command, AliasCommands::List),
+            Commands::Vault { command } => {
+                matches!(command, VaultCommands::List | VaultCommands::Info { .. })
+            }
+            Commands::Debug { command } => matches!(command, DebugCommands::Paths),
+            _ => false,
+        }
+    }
+}
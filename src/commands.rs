@@ -12,6 +12,15 @@ pub mod config;
 pub mod alias;
 pub mod vault;
 pub mod debug;
+pub mod scan;
+pub mod import;
+pub mod export;
+pub mod bundle;
+pub mod doctor;
+pub mod adopt;
+pub mod mv;
+pub mod relink;
+pub mod transfer;
 
 // Re-export commonly used top-level commands
 pub use init::init;
@@ -22,6 +31,14 @@ pub use restore::restore;
 pub use status::status;
 pub use unlink::unlink;
 pub use config::config;
+pub use scan::scan;
+pub use import::import;
+pub use export::export;
+pub use doctor::doctor;
+pub use adopt::adopt;
+pub use mv::mv;
+pub use relink::relink;
+pub use transfer::transfer;
 
-// Alias, vault and debug subcommands use full module paths for clarity
-// (e.g., commands::alias::add, commands::vault::create, commands::debug::show_paths)
+// Alias, vault, bundle and debug subcommands use full module paths for clarity
+// (e.g., commands::alias::add, commands::vault::create, commands::bundle::apply)
@@ -0,0 +1,254 @@
+// Adopt command implementation - maps files already in the vault repo to source paths
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use globset::Glob;
+use std::collections::BTreeSet;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+
+use crate::catalog;
+use crate::vault::Vault;
+use super::helpers::{get_vault_dir, get_active_vault_name, display_path, is_within, unmapped_files};
+use super::link::{self, LinkRequest};
+
+/// How the suggested source path was found
+enum Origin {
+    /// Known location of a catalog app (its name)
+    Catalog(&'static str),
+    /// `name/namerc` or `name/name`, as `gfv link` stores `~/.namerc` and `~/.name`
+    Dotfile,
+    /// Under `~/.config`, as `gfv link` stores `~/.config/...`
+    Guess,
+}
+
+/// A vault path that could become an entry
+struct Candidate {
+    vault_path: String,
+    source_path: PathBuf,
+    is_dir: bool,
+    platform: Option<String>,
+    origin: Origin,
+}
+
+pub fn adopt(
+    select: Vec<String>,
+    yes: bool,
+    vault: Option<String>,
+) -> Result<()> {
+    // Get vault directory
+    let vault_name = vault.unwrap_or_else(get_active_vault_name);
+    let vault_dir = get_vault_dir(&vault_name)?;
+
+    // Check if vault is initialized
+    if !Vault::is_initialized(&vault_dir) {
+        bail!("Vault not initialized. Run 'gfv init' first.");
+    }
+
+    // Load vault
+    let mut vault = Vault::load(&vault_dir)
+        .context("Failed to load vault")?;
+
+    let home = dirs::home_dir()
+        .context("Failed to get home directory")?;
+    let candidates = find_candidates(&vault, &home);
+    if candidates.is_empty() {
+        println!("{} Every file in the vault repo is managed", "✓".green());
+        return Ok(());
+    }
+
+    print_table(&candidates);
+
+    // Pick what to adopt: arguments, an interactive answer, or nothing
+    let (selected, confirmed) = if !select.is_empty() {
+        (choose(&candidates, &select)?, yes)
+    } else if std::io::stdin().is_terminal() {
+        println!("\nAdopt which? Numbers or ranges (1,3-5), vault paths, 'all', or Enter to skip:");
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+        let tokens: Vec<String> = input.split([',', ' ', '\t', '\n'])
+            .filter(|token| !token.trim().is_empty())
+            .map(|token| token.trim().to_string())
+            .collect();
+        if tokens.is_empty() {
+            return Ok(());
+        }
+        // Choosing from the table is the confirmation
+        (choose(&candidates, &tokens)?, true)
+    } else {
+        println!("\nAdopt them with: gfv adopt <number|vault path>... (or 'gfv adopt all')");
+        return Ok(());
+    };
+
+    let requests: Vec<LinkRequest> = selected.iter()
+        .map(|candidate| LinkRequest {
+            source_path: candidate.source_path.clone(),
+            vault_path: Some(candidate.vault_path.clone()),
+            platform: candidate.platform.clone(),
+        })
+        .collect();
+
+    println!();
+    let managed = vault.manifest.files.len();
+    link::link_requests(&mut vault, &requests, confirmed)?;
+    if vault.manifest.files.len() == managed {
+        return Ok(());
+    }
+
+    println!("\nDifferent source path for one of them? Link it with: gfv link <source> --name <vault path>");
+    println!("Preview what restore will change with: gfv restore --dry-run");
+    Ok(())
+}
+
+/// Group the unmapped files into entries and suggest where each one goes:
+/// catalog files first, then dotfiles, then whole top-level directories
+/// (or single files where a directory is partly managed)
+fn find_candidates(vault: &Vault, home: &Path) -> Vec<Candidate> {
+    let files = unmapped_files(vault);
+    let entries: Vec<&str> = vault.manifest.files.keys().map(String::as_str).collect();
+    let mut candidates: Vec<Candidate> = Vec::new();
+
+    // A vault path is free if no entry or other candidate overlaps it
+    let free = |candidates: &[Candidate], vault_path: &str| {
+        let overlaps = |taken: &str| is_within(vault_path, taken) || is_within(taken, vault_path);
+        !entries.iter().any(|taken| overlaps(taken))
+            && !candidates.iter().any(|candidate| overlaps(&candidate.vault_path))
+    };
+    let is_dir = |vault_path: &str| vault.get_file_path(vault_path).is_dir();
+
+    for app in catalog::apps() {
+        for file in &app.files {
+            let vault_path = file.vault_path.as_str();
+            if !files.iter().any(|path| is_within(path, vault_path)) || !free(&candidates, vault_path) {
+                continue;
+            }
+            if let Some((_, source_path)) = catalog::source_for(vault_path) {
+                candidates.push(Candidate {
+                    vault_path: vault_path.to_string(),
+                    source_path,
+                    is_dir: is_dir(vault_path),
+                    platform: app.platform.clone(),
+                    origin: Origin::Catalog(&app.name),
+                });
+            }
+        }
+    }
+
+    for path in &files {
+        let mut parts = path.split('/');
+        let (Some(base), Some(name)) = (parts.next(), parts.next()) else { continue };
+        if name != base && name != format!("{}rc", base) {
+            continue;
+        }
+        let vault_path = format!("{}/{}", base, name);
+        if free(&candidates, &vault_path) {
+            candidates.push(Candidate {
+                source_path: home.join(format!(".{}", name)),
+                is_dir: is_dir(&vault_path),
+                vault_path,
+                platform: None,
+                origin: Origin::Dotfile,
+            });
+        }
+    }
+
+    for path in &files {
+        if candidates.iter().any(|candidate| is_within(path, &candidate.vault_path)) {
+            continue;
+        }
+        let vault_path = match path.split_once('/') {
+            Some((top, _)) if free(&candidates, top) => top.to_string(),
+            _ => path.clone(),
+        };
+        candidates.push(Candidate {
+            source_path: guess_source(&vault_path, home),
+            is_dir: is_dir(&vault_path),
+            vault_path,
+            platform: None,
+            origin: Origin::Guess,
+        });
+    }
+
+    candidates.sort_by(|a, b| a.vault_path.cmp(&b.vault_path));
+    candidates
+}
+
+/// `~/.config/<vault path>`, unless only `~/<vault path>` exists on this machine
+fn guess_source(vault_path: &str, home: &Path) -> PathBuf {
+    let config = home.join(".config").join(vault_path);
+    let plain = home.join(vault_path);
+    if !config.exists() && plain.exists() {
+        plain
+    } else {
+        config
+    }
+}
+
+fn print_table(candidates: &[Candidate]) {
+    println!("{} Found {} unmanaged {} in the vault repo", "==>".green().bold(),
+        candidates.len(), if candidates.len() == 1 { "entry" } else { "entries" });
+
+    let sources: Vec<String> = candidates.iter().map(|c| display_path(&c.source_path)).collect();
+    let vault_width = candidates.iter().map(|c| c.vault_path.len()).max().unwrap_or(0).max("VAULT PATH".len());
+    let source_width = sources.iter().map(String::len).max().unwrap_or(0).max("SOURCE".len());
+
+    println!("\n  {:>3}  {:<vw$}  TYPE  {:<sw$}  FROM", "#", "VAULT PATH", "SOURCE",
+        vw = vault_width, sw = source_width);
+    for (number, (candidate, source)) in candidates.iter().zip(&sources).enumerate() {
+        let origin = match candidate.origin {
+            Origin::Catalog(app) => app.to_string(),
+            Origin::Dotfile => "dotfile".to_string(),
+            Origin::Guess => "guess".yellow().to_string(),
+        };
+        let platform = candidate.platform.as_deref()
+            .map(|platform| format!(" ({} only)", platform).dimmed().to_string())
+            .unwrap_or_default();
+        println!("  {:>3}  {:<vw$}  {:<4}  {:<sw$}  {}{}", number + 1, candidate.vault_path,
+            if candidate.is_dir { "dir" } else { "file" }, source, origin, platform,
+            vw = vault_width, sw = source_width);
+    }
+}
+
+/// Resolve selection tokens (numbers, ranges, vault paths or globs, `all`) against the candidates
+fn choose<'a>(candidates: &'a [Candidate], tokens: &[String]) -> Result<Vec<&'a Candidate>> {
+    let mut chosen = BTreeSet::new();
+
+    for token in tokens {
+        let token = token.trim();
+        if token.eq_ignore_ascii_case("all") {
+            chosen.extend(0..candidates.len());
+            continue;
+        }
+
+        let range = match token.split_once('-') {
+            Some((start, end)) => start.parse::<usize>().ok().zip(end.parse::<usize>().ok()),
+            None => token.parse::<usize>().ok().map(|n| (n, n)),
+        };
+        if let Some((start, end)) = range {
+            if start == 0 || end > candidates.len() || start > end {
+                bail!("Invalid selection '{}' (choose between 1 and {})", token, candidates.len());
+            }
+            chosen.extend(start - 1..end);
+            continue;
+        }
+
+        // A vault path or a glob over vault paths
+        let glob = Glob::new(token.trim_end_matches('/'))
+            .with_context(|| format!("Invalid pattern: {}", token))?
+            .compile_matcher();
+        let matches: Vec<usize> = candidates.iter()
+            .enumerate()
+            .filter(|(_, candidate)| glob.is_match(&candidate.vault_path))
+            .map(|(i, _)| i)
+            .collect();
+        if matches.is_empty() {
+            bail!("No unmanaged entry matches '{}'", token);
+        }
+        chosen.extend(matches);
+    }
+
+    if chosen.is_empty() {
+        bail!("Nothing selected");
+    }
+    Ok(chosen.into_iter().map(|i| &candidates[i]).collect())
+}
@@ -2,7 +2,9 @@
 
 use anyhow::{bail, Context, Result};
 use colored::Colorize;
+use std::collections::BTreeMap;
 use crate::config::Config;
+use crate::output::{self, OutputFormat};
 
 pub fn add(name: String, command: Vec<String>) -> Result<()> {
     // Validate alias name
@@ -89,11 +91,16 @@ pub fn remove(name: String) -> Result<()> {
     Ok(())
 }
 
-pub fn list() -> Result<()> {
+pub fn list(format: OutputFormat) -> Result<()> {
     // Load config
     let config = Config::load()
         .context("Failed to load config")?;
 
+    if format.is_json() {
+        let aliases: BTreeMap<_, _> = config.aliases.iter().collect();
+        return output::print_json(&aliases);
+    }
+
     if config.aliases.is_empty() {
         println!("No aliases configured.");
         println!("\nCreate an alias with:");
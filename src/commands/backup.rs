@@ -2,19 +2,28 @@
 
 use anyhow::{bail, Context, Result};
 use colored::Colorize;
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use std::fs;
+use std::path::Path;
 
-use crate::vault::Vault;
-use crate::git_ops::GitRepo;
-use crate::config::Config;
+use crate::vault::{commit_message, Vault};
+use crate::git_ops::{AuthOptions, GitRepo};
+use crate::vault::manifest::RemoteConfig;
 #[cfg(feature = "ai")]
-use crate::ai::AiClient;
-use super::helpers::{get_vault_dir, get_active_vault_name};
+use crate::ai::{redact, summarize, AiClient};
+#[cfg(feature = "ai")]
+use super::link::is_sensitive_file;
+#[cfg(feature = "ai")]
+use super::helpers::is_within;
+use super::helpers::{get_vault_dir, get_active_vault_name, select_entries};
 
-pub async fn backup(
+pub fn backup(
+    paths: Vec<String>,
     message: Option<String>,
-    _force: bool,
+    split: bool,
+    force: bool,
     _set_upstream: bool,
+    remote: Option<String>,
     vault: Option<String>,
 ) -> Result<()> {
     // Get vault directory
@@ -30,19 +39,40 @@ pub async fn backup(
     let vault = Vault::load(&vault_dir)
         .context("Failed to load vault")?;
 
+    if let Some(ref name) = remote {
+        if !vault.manifest.remotes.contains_key(name) {
+            bail!("Remote '{}' not found\n\nList remotes with:\n  gfv vault remote list", name);
+        }
+    }
+
     if vault.manifest.files.is_empty() {
         println!("No files to backup. Add files with 'gfv link <file>'.");
         return Ok(());
     }
 
+    // Entries to back up: all of them, or those selected on the command line
+    let selected = select_entries(&vault, &paths)?;
+
     println!("{} Backing up changes...", "==>".green().bold());
+    if !paths.is_empty() {
+        println!("  {} Selected {}", "→".blue(), selected.join(", "));
+    }
 
-    // Step 1: Copy all source files to vault
+    let ignore = build_ignore_set(&vault.settings.backup.ignore)
+        .context("Invalid backup.ignore")?;
+
+    // Step 1: Copy the selected source files to vault
     let mut files_copied = 0;
-    for (vault_relative_path, entry) in &vault.manifest.files {
+    for vault_relative_path in &selected {
+        let entry = &vault.manifest.files[vault_relative_path];
         let source_path = std::path::PathBuf::from(&entry.source_path);
         let vault_file_path = vault.get_file_path(vault_relative_path);
 
+        if ignore.is_match(vault_relative_path) {
+            println!("  {} Skipping {} (matches backup.ignore)", "→".blue(), vault_relative_path);
+            continue;
+        }
+
         // Skip if source doesn't exist
         if !source_path.exists() {
             println!("  {} Skipping {} (source not found)",
@@ -58,7 +88,7 @@ pub async fn backup(
             if vault_file_path.exists() {
                 fs::remove_dir_all(&vault_file_path)?;
             }
-            copy_dir_recursive(&source_path, &vault_file_path)?;
+            copy_dir_recursive(&source_path, &vault_file_path, Path::new(vault_relative_path), &ignore)?;
         } else {
             // Ensure parent directory exists
             if let Some(parent) = vault_file_path.parent() {
@@ -72,47 +102,66 @@ pub async fn backup(
 
     println!("  {} Copied {} files/directories", "✓".green(), files_copied);
 
-    // Step 2: Check if there are any Git changes
-    let git_repo = GitRepo::open(&vault.repo_path)
+    let mut git_repo = GitRepo::open(&vault.repo_path)
         .context("Failed to open git repository")?;
 
-    let has_changes = git_repo.has_changes()?;
-
-    // Step 3: Commit changes if there are any
-    if has_changes {
-        // Generate commit message
-        let commit_msg = if let Some(msg) = message {
-            msg
+    // Step 2: Stage and commit the changes, in one commit or one per entry
+    let mut has_changes = false;
+    if split {
+        for vault_path in &selected {
+            git_repo.stage_paths(&[vault_path.as_str()])
+                .context("Failed to stage changes")?;
+            if git_repo.has_staged_changes()? {
+                commit_staged(&git_repo, &vault, &vault_name, None)?;
+                has_changes = true;
+            }
+        }
+    } else {
+        if paths.is_empty() {
+            git_repo.add_all()
         } else {
-            // Try AI generation if configured
-            generate_commit_message_auto(&git_repo).await?
-        };
-
-        git_repo.add_all()
-            .context("Failed to stage changes")?;
-
-        git_repo.commit(&commit_msg)
-            .context("Failed to commit changes")?;
+            let pathspecs: Vec<&str> = selected.iter().map(String::as_str).collect();
+            git_repo.stage_paths(&pathspecs)
+        }
+        .context("Failed to stage changes")?;
 
-        println!("  {} Committed locally: \"{}\"", "✓".green(), commit_msg);
+        if git_repo.has_staged_changes()? {
+            commit_staged(&git_repo, &vault, &vault_name, message)?;
+            has_changes = true;
+        }
     }
 
-    // Step 4: Sync with remote (if configured)
-    if let Some(ref remote_config) = vault.manifest.remote {
+    // Step 4: Sync with remotes (if configured)
+    if let Some((primary, primary_config)) = vault.manifest.primary_remote() {
+        // Pull from the chosen remote (or the primary), then push to it
+        // and, unless a single remote was requested, to every mirror
+        let sync_name = remote.as_deref().unwrap_or(primary);
+        let Some(sync_config) = vault.manifest.remotes.get(sync_name) else {
+            bail!("Remote '{}' not found\n\nList remotes with:\n  gfv vault remote list", sync_name);
+        };
+
         println!("  {} Syncing with remote...", "==>".green());
 
         // Get the actual current branch (it might differ from manifest if repo was created with different default)
         let current_branch = git_repo.current_branch()
-            .unwrap_or_else(|_| remote_config.branch.clone());
+            .unwrap_or_else(|_| primary_config.branch.clone());
+
+        // The primary tracks the local branch; mirrors use their configured branch
+        let remote_branch = |name: &str, config: &RemoteConfig| {
+            if name == primary { current_branch.clone() } else { config.branch.clone() }
+        };
+        let sync_branch = remote_branch(sync_name, sync_config);
+
+        git_repo.set_auth(AuthOptions::from_remote(sync_config));
 
         // Try to fetch first to get remote refs (ignore errors if remote is empty/new)
-        let _ = git_repo.fetch("origin", &current_branch);
+        let _ = git_repo.fetch(sync_name, &sync_branch);
 
         // Only pull if remote branch exists (skip on first push)
-        if git_repo.remote_branch_exists("origin", &current_branch) {
-            match git_repo.pull("origin", &current_branch, true) {
+        if git_repo.remote_branch_exists(sync_name, &sync_branch) {
+            match git_repo.pull(sync_name, &sync_branch, true) {
                 Ok(_) => {
-                    println!("    {} Pulled from origin/{}", "✓".green(), current_branch);
+                    println!("    {} Pulled from {}/{}", "✓".green(), sync_name, sync_branch);
                 }
                 Err(e) => {
                     eprintln!("{} Failed to pull from remote: {}", "✗".red().bold(), e);
@@ -125,17 +174,42 @@ pub async fn backup(
             println!("    {} First push to remote (skipping pull)", "→".blue());
         }
 
+        let targets: Vec<(&String, &RemoteConfig)> = match remote {
+            Some(_) => vault.manifest.remotes.get_key_value(sync_name).into_iter().collect(),
+            None => {
+                // Primary first so a failing mirror never blocks the main backup
+                let mut targets: Vec<_> = vault.manifest.remotes.iter().collect();
+                targets.sort_by_key(|(name, _)| name.as_str() != primary);
+                targets
+            }
+        };
+
         // Push
-        match git_repo.push("origin", &current_branch) {
-            Ok(_) => {
-                println!("    {} Pushed to origin/{}", "✓".green(), current_branch);
-                println!("\n{} Your files are backed up to remote!", "✓".green().bold());
+        let mut failures = Vec::new();
+        for (name, config) in &targets {
+            let branch = remote_branch(name, config);
+            git_repo.set_auth(AuthOptions::from_remote(config));
+
+            match git_repo.push_branch(name, &current_branch, &branch, force) {
+                Ok(_) => {
+                    println!("    {} Pushed to {}/{}", "✓".green(), name, branch);
+                }
+                Err(e) => {
+                    println!("    {} Failed to push to {}/{}: {:#}", "✗".red(), name, branch, e);
+                    failures.push(name.to_string());
+                }
             }
-            Err(e) => {
-                eprintln!("{} Failed to push to remote: {}", "✗".red().bold(), e);
-                eprintln!("\nYour changes are committed locally but not pushed.");
-                return Err(e);
+        }
+
+        if failures.is_empty() {
+            if targets.len() > 1 {
+                println!("\n{} Your files are backed up to {} remotes!", "✓".green().bold(), targets.len());
+            } else {
+                println!("\n{} Your files are backed up to remote!", "✓".green().bold());
             }
+        } else {
+            eprintln!("\nYour changes are committed locally but not pushed to: {}", failures.join(", "));
+            bail!("Push failed for {} of {} remotes", failures.len(), targets.len());
         }
     } else {
         // No remote configured
@@ -150,60 +224,121 @@ pub async fn backup(
     Ok(())
 }
 
-/// Generate commit message automatically (using AI if configured, or fallback)
-async fn generate_commit_message_auto(git_repo: &GitRepo) -> Result<String> {
-    // Load global config
-    let config = Config::load().unwrap_or_else(|_| Config {
-        vaults: std::collections::HashMap::new(),
-        current: crate::config::CurrentConfig {
-            active: "default".to_string(),
-        },
-        ai: Default::default(),
-        sync: Default::default(),
-        aliases: std::collections::HashMap::new(),
-    });
+/// Commit what is staged, with the given message or a generated one
+fn commit_staged(git_repo: &GitRepo, vault: &Vault, vault_name: &str, message: Option<String>) -> Result<()> {
+    let commit_msg = match message {
+        Some(msg) => msg,
+        // AI if configured, otherwise a summary of the changed vault paths
+        None => generate_commit_message_auto(git_repo, vault, vault_name)?,
+    };
+
+    git_repo.commit(&commit_msg)
+        .context("Failed to commit changes")?;
+
+    let subject = commit_msg.lines().next().unwrap_or_default();
+    println!("  {} Committed locally: \"{}\"", "✓".green(), subject);
+    Ok(())
+}
+
+/// Generate commit message automatically (using AI if configured, or a local summary)
+#[cfg_attr(not(feature = "ai"), allow(unused_variables))]
+fn generate_commit_message_auto(git_repo: &GitRepo, vault: &Vault, vault_name: &str) -> Result<String> {
+    let files = git_repo.get_staged_diffs()
+        .context("Failed to get git diff")?;
 
     // Check if AI is configured
     #[cfg(feature = "ai")]
     {
-        if let (Some(endpoint), Some(api_key), Some(model)) = (
-            &config.ai.endpoint,
-            &config.ai.api_key,
-            &config.ai.model,
-        ) {
-            println!("  {} Generating commit message with AI...", "→".blue());
-
-            // Get the diff
-            let diff = git_repo.get_diff()
-                .context("Failed to get git diff")?;
-
-            if !diff.trim().is_empty() {
-                // Try to generate with AI
-                let ai_client = AiClient::new(
-                    endpoint.clone(),
-                    api_key.clone(),
-                    model.clone(),
-                );
-
-                match ai_client.generate_commit_message(&diff).await {
-                    Ok(message) => {
-                        return Ok(message);
-                    }
-                    Err(e) => {
-                        eprintln!("  {} AI generation failed: {}", "⚠".yellow(), e);
-                        eprintln!("  {} Falling back to default message", "→".yellow());
+        match AiClient::from_config(&vault.settings.ai, &vault.repo_path) {
+            Ok(Some(ai_client)) => {
+                println!("  {} Generating commit message with {}...", "→".blue(), ai_client.describe());
+
+                // Without sensitive entries and with secrets masked
+                let exclude = build_ignore_set(&vault.settings.ai.exclude)
+                    .context("Invalid ai.exclude")?;
+                let redacted = redact::redact_diffs(&files, |path| ai_withhold_reason(vault, &exclude, path));
+                report_redaction(&redacted);
+
+                if !redacted.files.is_empty() {
+                    // Every file is listed; patches share the token budget
+                    let budget = vault.settings.ai.token_budget
+                        .unwrap_or(summarize::DEFAULT_TOKEN_BUDGET);
+                    let summary = summarize::summarize(&redacted.files, budget);
+                    let runtime = tokio::runtime::Runtime::new()
+                        .context("Failed to start async runtime")?;
+                    match runtime.block_on(ai_client.generate_commit_message(&summary, vault_name)) {
+                        Ok(message) => {
+                            return Ok(message);
+                        }
+                        Err(e) => {
+                            eprintln!("  {} AI generation failed: {:#}", "⚠".yellow(), e);
+                            eprintln!("  {} Falling back to a summary of the changes", "→".yellow());
+                        }
                     }
                 }
             }
+            Ok(None) => {}
+            Err(e) => {
+                eprintln!("  {} AI is misconfigured: {:#}", "⚠".yellow(), e);
+                eprintln!("  {} Falling back to a summary of the changes", "→".yellow());
+            }
+        }
+    }
+
+    // Describe the changed vault paths locally
+    let presence = |path: &str| commit_message::Presence {
+        before: git_repo.head_has_path(path),
+        after: git_repo.index_has_path(path),
+    };
+    Ok(commit_message::commit_message(&files, &vault.manifest, &vault.settings.backup, presence)
+        .unwrap_or_else(|| "Update vault".to_string()))
+}
+
+/// Why a changed repo path must not be sent to the AI provider, if it must not
+#[cfg(feature = "ai")]
+fn ai_withhold_reason(vault: &Vault, exclude: &GlobSet, path: &str) -> Option<String> {
+    if exclude.is_match(path) {
+        return Some("ai.exclude".to_string());
+    }
+
+    let entry = vault.manifest.files.iter()
+        .find(|(vault_path, _)| is_within(path, vault_path))
+        .map(|(_, entry)| entry)?;
+    if entry.sensitive || is_sensitive_file(Path::new(&entry.source_path)) {
+        return Some("sensitive".to_string());
+    }
+    None
+}
+
+/// Show what was kept out of the AI request
+#[cfg(feature = "ai")]
+fn report_redaction(redacted: &redact::Redacted) {
+    if !redacted.withheld.is_empty() {
+        println!("  {} Withheld from AI:", "→".blue());
+        for item in &redacted.withheld {
+            println!("      {} ({})", item.path, item.reason);
         }
     }
+    if redacted.masked > 0 {
+        println!("  {} Masked {} secret-like {} before sending",
+            "→".blue(), redacted.masked, if redacted.masked == 1 { "value" } else { "values" });
+    }
+}
 
-    // Fallback to default message
-    Ok("Update vault".to_string())
+/// Build a matcher for glob patterns such as `backup.ignore` or `ai.exclude`
+fn build_ignore_set(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(pattern)
+            .with_context(|| format!("Invalid pattern: {}", pattern))?;
+        builder.add(glob);
+    }
+    builder.build().context("Failed to build patterns")
 }
 
-/// Recursively copy directory
-fn copy_dir_recursive(src: &std::path::Path, dst: &std::path::Path) -> Result<()> {
+/// Recursively copy directory, skipping paths matched by `ignore`.
+/// `vault_path` is the vault-relative path of `dst`, used for matching.
+fn copy_dir_recursive(src: &Path, dst: &Path, vault_path: &Path, ignore: &GlobSet) -> Result<()> {
     fs::create_dir_all(dst)?;
 
     for entry in fs::read_dir(src)? {
@@ -211,9 +346,14 @@ fn copy_dir_recursive(src: &std::path::Path, dst: &std::path::Path) -> Result<()
         let file_type = entry.file_type()?;
         let src_path = entry.path();
         let dst_path = dst.join(entry.file_name());
+        let entry_vault_path = vault_path.join(entry.file_name());
+
+        if ignore.is_match(&entry_vault_path) {
+            continue;
+        }
 
         if file_type.is_dir() {
-            copy_dir_recursive(&src_path, &dst_path)?;
+            copy_dir_recursive(&src_path, &dst_path, &entry_vault_path, ignore)?;
         } else {
             fs::copy(&src_path, &dst_path)?;
         }
@@ -0,0 +1,136 @@
+// Bundle commands - offline sync through git bundle files
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+
+use crate::git_ops::GitRepo;
+use crate::vault::Vault;
+use crate::vault::manifest::SharedFiles;
+use super::helpers::{get_vault_dir, get_active_vault_name, resolve_source_path, display_path};
+use super::restore::restore_files;
+
+/// Write the vault branch and its file entries to a bundle file
+pub fn create(file: String, since: Option<String>, vault: Option<String>) -> Result<()> {
+    // Get vault directory
+    let vault_name = vault.unwrap_or_else(get_active_vault_name);
+    let vault_dir = get_vault_dir(&vault_name)?;
+
+    // Check if vault is initialized
+    if !Vault::is_initialized(&vault_dir) {
+        bail!("Vault not initialized. Run 'gfv init' first.");
+    }
+
+    // Load vault
+    let vault = Vault::load(&vault_dir)
+        .context("Failed to load vault")?;
+
+    let git_repo = GitRepo::open(&vault.repo_path)
+        .context("Failed to open git repository")?;
+    if git_repo.is_empty()? {
+        bail!("Vault has no commits yet. Run 'gfv backup' first.");
+    }
+    let branch = git_repo.current_branch()?;
+
+    println!("{} Creating bundle of {}...", "==>".green().bold(), branch);
+    if git_repo.has_changes()? {
+        println!("  {} Uncommitted vault changes are not included; run 'gfv backup' first to include them",
+            "⚠".yellow());
+    }
+
+    let home = dirs::home_dir()
+        .context("Failed to get home directory")?;
+    let shared = vault.manifest.shared_files(&home);
+    let manifest = serde_json::to_vec_pretty(&shared)
+        .context("Failed to serialize manifest")?;
+
+    let bundle_path = resolve_source_path(&file)?;
+    let created = git_repo.create_bundle(&bundle_path, &branch, since.as_deref(), &manifest)?;
+
+    let size = std::fs::metadata(&bundle_path).map(|m| m.len()).unwrap_or(0);
+    println!("  {} {} {}{}", "✓".green(), created.commits,
+        if created.commits == 1 { "commit" } else { "commits" },
+        since.as_deref().map(|since| format!(" since {}", since)).unwrap_or_default());
+    println!("  {} {} {}", "✓".green(), shared.files.len(),
+        if shared.files.len() == 1 { "entry" } else { "entries" });
+
+    let tip = created.tip.to_string();
+    println!("\n{} Wrote {} ({} KB)", "✓".green().bold(), bundle_path.display(), size.div_ceil(1024));
+    println!("\nApply on another machine with:");
+    println!("  gfv bundle apply {}", bundle_path.file_name().unwrap_or_default().to_string_lossy());
+    println!("Once applied, bundle only newer commits with:");
+    println!("  gfv bundle create {} --since {}", file, &tip[..7]);
+
+    Ok(())
+}
+
+/// Bring a bundle's commits and file entries into the vault, then restore
+pub fn apply(file: String, force: bool, vault: Option<String>) -> Result<()> {
+    // Get vault directory
+    let vault_name = vault.unwrap_or_else(get_active_vault_name);
+    let vault_dir = get_vault_dir(&vault_name)?;
+
+    // Check if vault is initialized
+    if !Vault::is_initialized(&vault_dir) {
+        bail!("Vault not initialized. Run 'gfv init' first.");
+    }
+
+    // Load vault
+    let mut vault = Vault::load(&vault_dir)
+        .context("Failed to load vault")?;
+
+    let bundle_path = resolve_source_path(&file)?;
+    if !bundle_path.is_file() {
+        bail!("Bundle not found: {}", bundle_path.display());
+    }
+
+    let git_repo = GitRepo::open(&vault.repo_path)
+        .context("Failed to open git repository")?;
+    if git_repo.has_changes()? {
+        bail!("Vault has uncommitted changes. Run 'gfv backup' first.");
+    }
+
+    println!("{} Applying bundle {}...", "==>".green().bold(), bundle_path.display());
+
+    // Step 1: Fetch the bundle and bring its branch in
+    let new_vault = git_repo.is_fresh()?;
+    let fetched = git_repo.fetch_bundle(&bundle_path)?;
+    if git_repo.shares_history(&fetched.refname)? {
+        let branch = git_repo.current_branch()?;
+        if let Err(e) = git_repo.merge_fetched(&fetched.refname, &branch, true) {
+            eprintln!("{} Failed to merge bundle: {}", "✗".red().bold(), e);
+            eprintln!("\nResolve conflicts manually in: {}", vault.repo_path.display());
+            return Err(e);
+        }
+        println!("  {} Merged {} from bundle", "✓".green(), fetched.branch);
+    } else if new_vault {
+        // A new vault has no history of its own: take the bundle's
+        git_repo.reset_to(&fetched.refname)?;
+        println!("  {} Checked out {} from bundle", "✓".green(), fetched.branch);
+    } else {
+        bail!("Bundle history is unrelated to this vault. Apply it to a new vault instead.");
+    }
+
+    // Step 2: Take over file entries this vault doesn't have yet
+    if let Some(manifest) = fetched.manifest {
+        let shared: SharedFiles = serde_json::from_slice(&manifest)
+            .context("Failed to parse manifest in bundle")?;
+        let home = dirs::home_dir()
+            .context("Failed to get home directory")?;
+        let added = vault.manifest.add_shared_files(shared, &home);
+        if !added.is_empty() {
+            vault.save_manifest()?;
+            for vault_path in &added {
+                let entry = &vault.manifest.files[vault_path];
+                println!("  {} Added {} → {}", "→".blue(), vault_path,
+                    display_path(std::path::Path::new(&entry.source_path)));
+            }
+        }
+    }
+
+    // Step 3: Restore as after a pull
+    if vault.manifest.files.is_empty() {
+        println!("\n{} No linked files to restore.", "✓".green().bold());
+        return Ok(());
+    }
+    restore_files(&vault, force)
+}
@@ -2,185 +2,99 @@
 
 use anyhow::{bail, Context, Result};
 use colored::Colorize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 use crate::vault::Vault;
 use crate::config::Config;
-use super::helpers::{get_vault_dir, get_active_vault_name};
+use crate::git_ops::GitRepo;
+use crate::config::settings::{self, Layers, Origin, KEYS};
+use crate::output::{self, OutputFormat};
 
+/// Which file `gfv config` writes to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    /// Global config file
+    Global,
+    /// Per-vault settings (<vault_dir>/config.toml)
+    Local,
+    /// Shared settings committed in the vault repo (repo/.gfv.toml)
+    Shared,
+    /// Secrets file next to the global config (credentials.toml, mode 0600)
+    Credentials,
+}
+
+/// A resolved key for display
+struct Entry {
+    key: String,
+    value: Option<String>,
+    origin: Option<Origin>,
+}
+
+#[derive(Serialize)]
+struct OriginValue {
+    value: Option<String>,
+    origin: Option<String>,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn config(
     key: Option<String>,
     value: Option<String>,
     list: bool,
     unset: Option<String>,
+    show_origin: bool,
+    scope: Scope,
+    vault: Option<String>,
+    format: OutputFormat,
 ) -> Result<()> {
-    // Get vault path
-    let vault_name = get_active_vault_name();
-    let vault_dir = get_vault_dir(&vault_name)?;
-    // Vault dir obtained above
+    // Settings work without an initialized vault; vault keys are shown when there is one
+    let global = Config::load()?;
+    let vault_name = vault.unwrap_or_else(|| global.current.active.clone());
+    let vault_dir = global.get_vault_dir(&vault_name)
+        .filter(|dir| Vault::is_initialized(dir));
 
-    // Check if vault is initialized
-    if !Vault::is_initialized(&vault_dir) {
-        bail!("Vault not initialized. Run 'gfv init' first.");
+    if matches!(scope, Scope::Local | Scope::Shared) && vault_dir.is_none() {
+        bail!("Vault '{}' is not initialized. Run 'gfv init' first.", vault_name);
     }
 
-    // Load vault
-    let vault = Vault::load(&vault_dir)
-        .context("Failed to load vault")?;
+    let layers = Layers::load(vault_dir.as_deref())?;
+    let vault = vault_dir.as_deref().map(Vault::load).transpose()?;
 
     if list {
-        // List all configuration
-        println!("{}", "Vault Configuration:".bold());
-        println!("\n{}", "General:".bold());
-        println!("  vault.dir = {}", vault.vault_dir.display());
-        println!("  vault.repo = {}", vault.repo_path.display());
-
-        if let Some(ref remote_config) = vault.manifest.remote {
-            println!("\n{}", "Remote:".bold());
-            println!("  remote.url = {}", remote_config.url);
-            println!("  remote.branch = {}", remote_config.branch);
-        } else {
-            println!("\n{}", "Remote:".bold());
-            println!("  {} (local-only mode)", "Not configured".yellow());
-        }
-
-        // AI configuration (read from global config)
-        let global_config = Config::load().unwrap_or_default();
-        println!("\n{}", "AI:".bold());
-        if let Some(ref endpoint) = global_config.ai.endpoint {
-            println!("  ai.endpoint = {}", endpoint);
-        } else {
-            println!("  ai.endpoint = {}", "Not configured".yellow());
+        let entries = list_entries(&layers, vault.as_ref());
+        if format.is_json() {
+            return print_json(&entries, show_origin);
         }
-        if global_config.ai.api_key.is_some() {
-            println!("  ai.api_key = {}", "****** (set)".green());
-        } else {
-            println!("  ai.api_key = {}", "Not configured".yellow());
-        }
-        if let Some(ref model) = global_config.ai.model {
-            println!("  ai.model = {}", model);
-        } else {
-            println!("  ai.model = {}", "Not configured".yellow());
-        }
-
+        print_list(&entries, show_origin);
         return Ok(());
     }
 
     if let Some(unset_key) = unset {
-        // Unset a key - support AI config keys
-        if unset_key.starts_with("ai.") {
-            let mut global_config = Config::load().unwrap_or_default();
-
-            match unset_key.as_str() {
-                "ai.endpoint" => {
-                    global_config.ai.endpoint = None;
-                    global_config.save()?;
-                    println!("{} Unset ai.endpoint", "✓".green());
-                }
-                "ai.api_key" => {
-                    global_config.ai.api_key = None;
-                    global_config.save()?;
-                    println!("{} Unset ai.api_key", "✓".green());
-                }
-                "ai.model" => {
-                    global_config.ai.model = None;
-                    global_config.save()?;
-                    println!("{} Unset ai.model", "✓".green());
-                }
-                _ => {
-                    bail!("Unknown configuration key: {}", unset_key);
-                }
-            }
-            return Ok(());
-        }
-
-        println!("Unsetting configuration is not yet implemented: {}", unset_key);
-        bail!("Feature not implemented");
+        return unset_value(&layers, &unset_key, scope, vault_dir);
     }
 
     if let (Some(ref k), Some(ref v)) = (&key, &value) {
-        // Set a key - support AI config keys
-        if k.starts_with("ai.") {
-            let mut global_config = Config::load().unwrap_or_default();
-
-            match k.as_str() {
-                "ai.endpoint" => {
-                    global_config.ai.endpoint = Some(v.clone());
-                    global_config.save()?;
-                    println!("{} Set ai.endpoint = {}", "✓".green(), v);
-                }
-                "ai.api_key" => {
-                    global_config.ai.api_key = Some(v.clone());
-                    global_config.save()?;
-                    println!("{} Set ai.api_key = ******", "✓".green());
-                }
-                "ai.model" => {
-                    global_config.ai.model = Some(v.clone());
-                    global_config.save()?;
-                    println!("{} Set ai.model = {}", "✓".green(), v);
-                }
-                _ => {
-                    bail!("Unknown configuration key: {}", k);
-                }
-            }
-            return Ok(());
-        }
-
-        println!("Setting configuration is not yet implemented: {} = {}", k, v);
-        bail!("Feature not implemented");
+        return set_value(&layers, k, v, scope, vault_dir);
     }
 
     if let Some(k) = key {
         // Get a single key
-        match k.as_str() {
-            "vault.dir" => {
-                println!("{}", vault.vault_dir.display());
-            }
-            "vault.repo" => {
-                println!("{}", vault.repo_path.display());
-            }
-            "remote.url" => {
-                if let Some(ref remote_config) = vault.manifest.remote {
-                    println!("{}", remote_config.url);
-                } else {
-                    println!("{}", "Not configured".yellow());
-                }
+        let entry = match vault_entries(vault.as_ref()).into_iter().find(|e| e.key == k) {
+            Some(entry) => entry,
+            None => {
+                settings::writable_key(&k)?;
+                resolve(&layers, &k)
             }
-            "remote.branch" => {
-                if let Some(ref remote_config) = vault.manifest.remote {
-                    println!("{}", remote_config.branch);
-                } else {
-                    println!("{}", "Not configured".yellow());
-                }
-            }
-            "ai.endpoint" => {
-                let global_config = Config::load().unwrap_or_default();
-                if let Some(ref endpoint) = global_config.ai.endpoint {
-                    println!("{}", endpoint);
-                } else {
-                    println!("{}", "Not configured".yellow());
-                }
-            }
-            "ai.api_key" => {
-                let global_config = Config::load().unwrap_or_default();
-                if global_config.ai.api_key.is_some() {
-                    println!("{}", "****** (set)".green());
-                } else {
-                    println!("{}", "Not configured".yellow());
-                }
-            }
-            "ai.model" => {
-                let global_config = Config::load().unwrap_or_default();
-                if let Some(ref model) = global_config.ai.model {
-                    println!("{}", model);
-                } else {
-                    println!("{}", "Not configured".yellow());
-                }
-            }
-            _ => {
-                bail!("Unknown configuration key: {}", k);
-            }
-        }
+        };
 
+        let shown = entry.value.unwrap_or_else(|| "Not configured".yellow().to_string());
+        if show_origin {
+            println!("{}\t{}", origin_label(&entry.origin), shown);
+        } else {
+            println!("{}", shown);
+        }
         return Ok(());
     }
 
@@ -190,6 +104,213 @@ pub fn config(
     println!("  gfv config <key>               # Get a configuration value");
     println!("  gfv config <key> <value>       # Set a configuration value");
     println!("  gfv config --unset <key>       # Unset a configuration value");
+    println!("  gfv config --local <key> <v>   # Set a value for this vault only");
+    println!("  gfv config --vault-shared <key> <v>  # Set and commit a value in repo/.gfv.toml");
+    println!("  gfv config --show-origin ...   # Show where each value comes from");
+
+    println!("\n{}", "Keys:".bold());
+    for def in KEYS {
+        println!("  {:<24} {}", def.key, def.description.dimmed());
+    }
+    println!("  {:<24} {}", "aliases.<name>", "Command alias".dimmed());
+    println!("\nPrecedence: env (GFV_<SECTION>_<KEY>) > vault > vault-shared > credentials > global > defaults");
+
+    Ok(())
+}
+
+/// Resolve a key through the layers, masking secrets
+fn resolve(layers: &Layers, key: &str) -> Entry {
+    let secret = settings::key_def(key).is_some_and(|def| def.secret);
+    match layers.resolve(key) {
+        Some((value, origin)) => Entry {
+            key: key.to_string(),
+            value: Some(if secret { "******".to_string() } else { settings::display_value(value) }),
+            origin: Some(origin.clone()),
+        },
+        None => Entry { key: key.to_string(), value: None, origin: None },
+    }
+}
+
+/// Read-only keys describing the vault itself
+fn vault_entries(vault: Option<&Vault>) -> Vec<Entry> {
+    let Some(vault) = vault else {
+        return Vec::new();
+    };
+
+    let mut entries = Vec::new();
+    let mut push = |key: String, value: Option<String>| {
+        let origin = value.as_ref().map(|_| Origin::Vault);
+        entries.push(Entry { key, value, origin });
+    };
+
+    push("vault.dir".to_string(), Some(vault.vault_dir.display().to_string()));
+    push("vault.repo".to_string(), Some(vault.repo_path.display().to_string()));
+
+    if let Some((remote_name, remote_config)) = vault.manifest.primary_remote() {
+        push("remote.name".to_string(), Some(remote_name.to_string()));
+        push("remote.url".to_string(), Some(remote_config.url.clone()));
+        push("remote.branch".to_string(), Some(remote_config.branch.clone()));
+        for (name, mirror) in &vault.manifest.remotes {
+            if name != remote_name {
+                push(format!("remote.{}.url", name), Some(mirror.url.clone()));
+                push(format!("remote.{}.branch", name), Some(mirror.branch.clone()));
+            }
+        }
+    } else {
+        push("remote.url".to_string(), None);
+        push("remote.branch".to_string(), None);
+    }
+
+    entries
+}
+
+/// Every key: vault info, known settings, then aliases
+fn list_entries(layers: &Layers, vault: Option<&Vault>) -> Vec<Entry> {
+    let mut entries = vault_entries(vault);
+    entries.extend(KEYS.iter().map(|def| resolve(layers, def.key)));
+    entries.extend(layers.alias_keys().iter().map(|key| resolve(layers, key)));
+    entries
+}
+
+fn origin_label(origin: &Option<Origin>) -> String {
+    origin.as_ref().map(|o| o.to_string()).unwrap_or_else(|| "-".to_string())
+}
+
+fn section_title(section: &str) -> &str {
+    match section {
+        "vault" => "General:",
+        "remote" => "Remote:",
+        "ai" => "AI:",
+        "sync" => "Sync:",
+        "ui" => "UI:",
+        "backup" => "Backup:",
+        "aliases" => "Aliases:",
+        other => other,
+    }
+}
+
+fn print_list(entries: &[Entry], show_origin: bool) {
+    println!("{}", "Configuration:".bold());
+
+    let mut current_section = "";
+    for entry in entries {
+        let section = entry.key.split('.').next().unwrap_or_default();
+        if section != current_section {
+            println!("\n{}", section_title(section).bold());
+            current_section = section;
+        }
+
+        let value = match entry.value {
+            Some(ref v) => v.clone(),
+            None => "Not configured".yellow().to_string(),
+        };
+        if show_origin {
+            println!("  {:<12} {} = {}", origin_label(&entry.origin).dimmed(), entry.key, value);
+        } else {
+            println!("  {} = {}", entry.key, value);
+        }
+    }
+}
+
+/// Flat key/value view of the configuration for `--format json`.
+/// Unset keys are null; the API key is never printed.
+fn print_json(entries: &[Entry], show_origin: bool) -> Result<()> {
+    if show_origin {
+        let values: BTreeMap<_, _> = entries.iter()
+            .map(|e| (e.key.as_str(), OriginValue {
+                value: e.value.clone(),
+                origin: e.origin.as_ref().map(|o| o.to_string()),
+            }))
+            .collect();
+        return output::print_json(&values);
+    }
+
+    let values: BTreeMap<_, _> = entries.iter()
+        .map(|e| (e.key.as_str(), e.value.as_deref()))
+        .collect();
+    output::print_json(&values)
+}
+
+/// File and layer written for a scope
+fn target(scope: Scope, vault_dir: Option<PathBuf>) -> Result<(PathBuf, Origin)> {
+    match (scope, vault_dir) {
+        (Scope::Global, _) => Ok((Config::config_path()?, Origin::Global)),
+        (Scope::Credentials, _) => Ok((Config::credentials_path()?, Origin::Credentials)),
+        (Scope::Local, Some(dir)) => Ok((settings::vault_config_path(&dir), Origin::Vault)),
+        (Scope::Shared, Some(dir)) => Ok((settings::shared_config_path(&dir), Origin::Shared)),
+        (_, None) => bail!("No initialized vault to write to"),
+    }
+}
+
+/// Commit a change to the shared settings file so it travels with the vault
+fn commit_shared(vault_dir: &Path, message: &str) -> Result<()> {
+    let git_repo = GitRepo::open(&vault_dir.join("repo"))
+        .context("Failed to open git repository")?;
+    git_repo.stage_path(Path::new(settings::SHARED_CONFIG_FILE))?;
+    git_repo.commit(message)?;
+
+    println!("  {} Committed {} (run 'gfv backup' to push)", "✓".green(), settings::SHARED_CONFIG_FILE);
+    Ok(())
+}
+
+fn warn_if_overridden(layers: &Layers, key: &str, origin: &Origin) {
+    if let Some(higher) = layers.overridden_above(key, origin) {
+        println!("{} {} is overridden by {} and will not take effect",
+            "Warning:".yellow().bold(), key, higher);
+    }
+}
+
+fn set_value(layers: &Layers, key: &str, value: &str, scope: Scope, vault_dir: Option<PathBuf>) -> Result<()> {
+    let def = settings::writable_key(key)?;
+    if scope == Scope::Credentials && !def.secret {
+        bail!("Only secret keys (such as ai.api_key) can be stored in the credentials file");
+    }
+    if def.global_only && !matches!(scope, Scope::Global | Scope::Credentials) {
+        bail!("{} can only be set in the global config", key);
+    }
+    if scope == Scope::Shared && !def.shared {
+        bail!("{} cannot be shared with the vault; set it globally or with --local", key);
+    }
+    let parsed = settings::parse_value(def, key, value)?;
+
+    let (path, origin) = target(scope, vault_dir.clone())?;
+    let mut table = settings::read_table(&path)?;
+    settings::set_path(&mut table, key, parsed.clone())?;
+    if def.secret {
+        settings::restrict_permissions(&path)?;
+    }
+    settings::write_table(&path, &table)?;
+
+    let shown = if def.secret { "******".to_string() } else { settings::display_value(&parsed) };
+    println!("{} Set {} = {} ({})", "✓".green(), key, shown, origin);
+    if def.secret && scope == Scope::Global {
+        println!("{} The key is stored in plain text in {}. Consider --credentials, ai.api_key_env or ai.api_key_cmd",
+            "Note:".yellow().bold(), path.display());
+    }
+    if let (Scope::Shared, Some(dir)) = (scope, vault_dir) {
+        commit_shared(&dir, &format!("Set shared setting {} = {}", key, shown))?;
+    }
+    warn_if_overridden(layers, key, &origin);
+
+    Ok(())
+}
+
+fn unset_value(layers: &Layers, key: &str, scope: Scope, vault_dir: Option<PathBuf>) -> Result<()> {
+    settings::writable_key(key)?;
+
+    let (path, origin) = target(scope, vault_dir.clone())?;
+    let mut table = settings::read_table(&path)?;
+    if settings::remove_path(&mut table, key).is_none() {
+        println!("{} {} is not set in the {} config", "→".blue(), key, origin);
+        return Ok(());
+    }
+    settings::write_table(&path, &table)?;
+
+    println!("{} Unset {} ({})", "✓".green(), key, origin);
+    if let (Scope::Shared, Some(dir)) = (scope, vault_dir) {
+        commit_shared(&dir, &format!("Unset shared setting {}", key))?;
+    }
+    warn_if_overridden(layers, key, &origin);
 
     Ok(())
 }
@@ -1,11 +1,44 @@
 // Debug command - for development and troubleshooting
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use colored::Colorize;
+use serde::Serialize;
+use std::path::Path;
 
-pub fn show_paths() -> Result<()> {
-    let home = dirs::home_dir()
-        .context("Failed to get home directory")?;
+use crate::output::{self, OutputFormat};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PathsOutput {
+    config_file: PathStatus,
+    vault_directory: PathStatus,
+    vaults_found: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct PathStatus {
+    path: String,
+    exists: bool,
+}
+
+impl PathStatus {
+    fn of(path: &Path) -> Self {
+        PathStatus { path: path.display().to_string(), exists: path.exists() }
+    }
+}
+
+pub fn show_paths(format: OutputFormat) -> Result<()> {
+    let gfv_home = crate::config::gfv_home()?;
+
+    if format.is_json() {
+        let config_path = crate::config::Config::config_path()?;
+        let vault_dir = gfv_home;
+        return output::print_json(&PathsOutput {
+            config_file: PathStatus::of(&config_path),
+            vaults_found: find_vault_dirs(&vault_dir),
+            vault_directory: PathStatus::of(&vault_dir),
+        });
+    }
 
     println!("{}", "GFV Paths:".cyan().bold());
     println!();
@@ -22,27 +55,20 @@ pub fn show_paths() -> Result<()> {
     println!();
 
     // Vault directory
-    let vault_dir = home.join(".gfv");
+    let vault_dir = gfv_home;
     println!("Vault directory:");
-    println!("  {}", vault_dir.display());
+    if std::env::var_os(crate::config::GFV_HOME_ENV).is_some_and(|v| !v.is_empty()) {
+        println!("  {} (from ${})", vault_dir.display(), crate::config::GFV_HOME_ENV);
+    } else {
+        println!("  {}", vault_dir.display());
+    }
     if vault_dir.exists() {
         println!("  Status: {}", "EXISTS".green());
 
         // List vaults
-        if let Ok(entries) = std::fs::read_dir(&vault_dir) {
-            let mut vaults = vec![];
-            for entry in entries.flatten() {
-                if entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
-                    if let Some(name) = entry.file_name().to_str() {
-                        if name != "." && name != ".." {
-                            vaults.push(name.to_string());
-                        }
-                    }
-                }
-            }
-            if !vaults.is_empty() {
-                println!("  Vaults found: {}", vaults.join(", "));
-            }
+        let vaults = find_vault_dirs(&vault_dir);
+        if !vaults.is_empty() {
+            println!("  Vaults found: {}", vaults.join(", "));
         }
     } else {
         println!("  Status: {}", "NOT FOUND".yellow());
@@ -52,17 +78,41 @@ pub fn show_paths() -> Result<()> {
     Ok(())
 }
 
-pub fn clean(force: bool) -> Result<()> {
-    let home = dirs::home_dir()
-        .context("Failed to get home directory")?;
+/// Directory names under the gfv home
+fn find_vault_dirs(vault_dir: &Path) -> Vec<String> {
+    let mut vaults = vec![];
+    if let Ok(entries) = std::fs::read_dir(vault_dir) {
+        for entry in entries.flatten() {
+            if entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+                if let Some(name) = entry.file_name().to_str() {
+                    if name != "." && name != ".." {
+                        vaults.push(name.to_string());
+                    }
+                }
+            }
+        }
+    }
+    vaults.sort();
+    vaults
+}
 
-    let gfv_dir = home.join(".gfv");
+pub fn clean(force: bool) -> Result<()> {
+    // Only ever delete the resolved gfv home, never a hard-coded path
+    let gfv_dir = crate::config::gfv_home()?;
 
     if !gfv_dir.exists() {
-        println!("Nothing to clean - ~/.gfv does not exist");
+        println!("Nothing to clean - {} does not exist", gfv_dir.display());
         return Ok(());
     }
 
+    // Guard against GFV_HOME pointing at something that is clearly not a gfv home
+    let gfv_dir = gfv_dir.canonicalize()
+        .with_context(|| format!("Failed to resolve {}", gfv_dir.display()))?;
+    let user_home = dirs::home_dir().and_then(|h| h.canonicalize().ok());
+    if gfv_dir.parent().is_none() || Some(&gfv_dir) == user_home.as_ref() {
+        bail!("Refusing to delete {} - check ${}", gfv_dir.display(), crate::config::GFV_HOME_ENV);
+    }
+
     println!("{} This will delete:", "⚠".yellow().bold());
     println!("  {}", gfv_dir.display());
     println!();
@@ -72,6 +122,12 @@ pub fn clean(force: bool) -> Result<()> {
     println!("  - All manifests");
     println!();
     println!("{} Source files (dotfiles, etc.) will NOT be deleted", "→".blue());
+    let config_path = crate::config::Config::config_path()?;
+    let config_path = config_path.canonicalize().unwrap_or(config_path);
+    if !config_path.starts_with(&gfv_dir) {
+        println!("{} Config file {} is outside this directory and will NOT be deleted",
+            "→".blue(), config_path.display());
+    }
     println!();
 
     if !force {
@@ -86,7 +142,7 @@ pub fn clean(force: bool) -> Result<()> {
     }
 
     std::fs::remove_dir_all(&gfv_dir)
-        .context("Failed to delete ~/.gfv")?;
+        .with_context(|| format!("Failed to delete {}", gfv_dir.display()))?;
 
     println!("{} Deleted {}", "✓".green().bold(), gfv_dir.display());
 
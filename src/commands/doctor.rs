@@ -0,0 +1,369 @@
+// Doctor command implementation - finds broken state and repairs what is safe to repair
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+
+use crate::config::{self, Config};
+use crate::git_ops::GitRepo;
+use crate::git_ops::bundle::BUNDLE_REMOTE;
+use crate::vault::Vault;
+use super::helpers::{get_vault_dir, display_path, is_within, vault_support_files};
+
+/// Something wrong with the setup
+struct Finding {
+    /// What it is about: a vault, an entry, a remote
+    subject: String,
+    problem: String,
+    explanation: String,
+    /// Odd but harmless: reported, never counted as a problem
+    warning: bool,
+    fix: Option<Fix>,
+}
+
+/// A repair that loses nothing
+enum Fix {
+    /// Drop a vault from the config, making another one active if it was
+    RemoveVault { name: String, next_active: Option<String> },
+    /// Make an existing vault active
+    SwitchActive(String),
+    /// Check an entry out of the last commit
+    CheckoutEntry { repo: PathBuf, vault_path: String },
+    /// Point the git remote at the URL in the manifest (adding it if missing)
+    SetRemote { repo: PathBuf, name: String, url: String },
+    /// Remove a git remote gfv left behind
+    RemoveRemote { repo: PathBuf, name: String },
+    /// Abort an unfinished rebase, merge or bisect
+    AbortOperation { repo: PathBuf, operation: &'static str },
+}
+
+impl Fix {
+    fn describe(&self) -> String {
+        match self {
+            Fix::RemoveVault { next_active: Some(next), .. } => {
+                format!("remove it from the config and make '{}' active", next)
+            }
+            Fix::RemoveVault { .. } => "remove it from the config".to_string(),
+            Fix::SwitchActive(name) => format!("make '{}' active", name),
+            Fix::CheckoutEntry { .. } => "restore it from the last commit".to_string(),
+            Fix::SetRemote { url, .. } => format!("set the git remote to {}", url),
+            Fix::RemoveRemote { .. } => "remove the git remote".to_string(),
+            Fix::AbortOperation { operation, .. } => format!("abort the {}", operation),
+        }
+    }
+
+    fn apply(&self, config: &mut Config) -> Result<()> {
+        match self {
+            Fix::RemoveVault { name, next_active } => {
+                config.vaults.remove(name);
+                if let Some(next) = next_active {
+                    config.current.active = next.clone();
+                }
+            }
+            Fix::SwitchActive(name) => config.current.active = name.clone(),
+            Fix::CheckoutEntry { repo, vault_path } => GitRepo::open(repo)?.checkout_path(vault_path)?,
+            Fix::SetRemote { repo, name, url } => GitRepo::open(repo)?.set_remote(name, url)?,
+            Fix::RemoveRemote { repo, name } => GitRepo::open(repo)?.remove_remote(name)?,
+            Fix::AbortOperation { repo, .. } => GitRepo::open(repo)?.abort_operation()?,
+        }
+        Ok(())
+    }
+
+    fn changes_config(&self) -> bool {
+        matches!(self, Fix::RemoveVault { .. } | Fix::SwitchActive(_))
+    }
+}
+
+pub fn doctor(fix: bool, vault: Option<String>) -> Result<()> {
+    let mut config = Config::load()?;
+    let mut findings = Vec::new();
+
+    println!("{} Checking config...", "==>".green().bold());
+    let found = check_config(&config);
+    report(&found);
+    findings.extend(found);
+
+    // The named vault, or every registered vault that still exists
+    let vaults: Vec<(String, PathBuf)> = match vault {
+        Some(name) => vec![(name.clone(), get_vault_dir(&name)?)],
+        None if config.vaults.is_empty() => vec![("default".to_string(), config::gfv_home()?.join("default"))],
+        None => {
+            let mut vaults: Vec<_> = config.vaults.iter()
+                .map(|(name, path)| (name.clone(), PathBuf::from(path)))
+                .filter(|(_, path)| Vault::is_initialized(path))
+                .collect();
+            vaults.sort();
+            vaults
+        }
+    };
+
+    for (name, vault_dir) in vaults {
+        println!("\n{} Checking vault '{}'...", "==>".green().bold(), name);
+        let found = check_vault(&vault_dir);
+        report(&found);
+        findings.extend(found);
+    }
+
+    let (warnings, problems): (Vec<_>, Vec<_>) = findings.iter().partition(|f| f.warning);
+    println!();
+    if problems.is_empty() {
+        println!("{} No problems found", "✓".green().bold());
+        if !warnings.is_empty() {
+            println!("({} {} above)", warnings.len(), if warnings.len() == 1 { "warning" } else { "warnings" });
+        }
+        return Ok(());
+    }
+
+    let fixable: Vec<_> = problems.iter().filter_map(|f| f.fix.as_ref()).collect();
+    if !fix {
+        if !fixable.is_empty() {
+            println!("Run 'gfv doctor --fix' to repair {} of them.", fixable.len());
+        }
+        bail!("Found {} {}", problems.len(), if problems.len() == 1 { "problem" } else { "problems" });
+    }
+
+    println!("{} Repairing...", "==>".green().bold());
+    let mut repaired = 0;
+    let mut config_changed = false;
+    for finding in &problems {
+        let Some(fix) = &finding.fix else { continue };
+        match fix.apply(&mut config) {
+            Ok(()) => {
+                println!("  {} {}: {}", "✓".green(), finding.subject, fix.describe());
+                repaired += 1;
+                config_changed |= fix.changes_config();
+            }
+            Err(e) => println!("  {} {}: {:#}", "✗".red(), finding.subject, e),
+        }
+    }
+    if config_changed {
+        config.save()?;
+    }
+
+    println!();
+    let remaining = problems.len() - repaired;
+    println!("{} Repaired {} {}", "✓".green().bold(), repaired, if repaired == 1 { "problem" } else { "problems" });
+    if remaining > 0 {
+        bail!("{} {} need manual attention (see above)", remaining, if remaining == 1 { "problem" } else { "problems" });
+    }
+    Ok(())
+}
+
+/// Print findings under the current heading
+fn report(findings: &[Finding]) {
+    if findings.is_empty() {
+        println!("  {} No problems found", "✓".green());
+        return;
+    }
+    for finding in findings {
+        let mark = if finding.warning { "⚠".yellow() } else { "✗".red() };
+        println!("  {} {}: {}", mark, finding.subject.bold(), finding.problem);
+        println!("      {}", finding.explanation);
+        if let Some(fix) = &finding.fix {
+            println!("      {} {}", "Fix (--fix):".cyan(), fix.describe());
+        }
+    }
+}
+
+/// Vaults in the config that point nowhere, and the active vault
+fn check_config(config: &Config) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    if config.vaults.is_empty() {
+        return findings;
+    }
+
+    let mut names: Vec<&String> = config.vaults.keys().collect();
+    names.sort();
+    let usable: Vec<&String> = names.iter()
+        .copied()
+        .filter(|name| Vault::is_initialized(Path::new(&config.vaults[*name])))
+        .collect();
+
+    for name in &names {
+        let path = Path::new(&config.vaults[*name]);
+        if !path.exists() {
+            let next_active = (config.current.active == **name)
+                .then(|| usable.first().map(|next| next.to_string()))
+                .flatten();
+            findings.push(Finding {
+                subject: format!("vault '{}'", name),
+                problem: "directory is missing".to_string(),
+                explanation: format!("The config points to {}, which no longer exists.", display_path(path)),
+                warning: false,
+                fix: Some(Fix::RemoveVault { name: name.to_string(), next_active }),
+            });
+        } else if !Vault::is_initialized(path) {
+            findings.push(Finding {
+                subject: format!("vault '{}'", name),
+                problem: "not initialized".to_string(),
+                explanation: format!("{} has no vault repository. Run 'gfv init' there, or 'gfv vault remove {}'.",
+                    display_path(path), name),
+                warning: false,
+                fix: None,
+            });
+        }
+    }
+
+    if !config.vaults.contains_key(&config.current.active) {
+        findings.push(Finding {
+            subject: format!("active vault '{}'", config.current.active),
+            problem: "not in the config".to_string(),
+            explanation: "Commands without --vault have no vault to work on.".to_string(),
+            warning: false,
+            fix: usable.first().map(|name| Fix::SwitchActive(name.to_string())),
+        });
+    }
+
+    findings
+}
+
+/// Manifest entries, repo files, remotes and repository state of one vault
+fn check_vault(vault_dir: &Path) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let problem = |subject: &str, problem: &str, explanation: String| Finding {
+        subject: subject.to_string(),
+        problem: problem.to_string(),
+        explanation,
+        warning: false,
+        fix: None,
+    };
+
+    if !Vault::is_initialized(vault_dir) {
+        findings.push(problem("vault", "not initialized",
+            format!("{} has no vault repository. Run 'gfv init' first.", display_path(vault_dir))));
+        return findings;
+    }
+    let vault = match Vault::load(vault_dir) {
+        Ok(vault) => vault,
+        Err(e) => {
+            findings.push(problem("manifest", "cannot be read", format!("{:#}", e)));
+            return findings;
+        }
+    };
+    let git_repo = match GitRepo::open(&vault.repo_path) {
+        Ok(git_repo) => git_repo,
+        Err(e) => {
+            findings.push(problem("repository", "cannot be opened", format!("{:#}", e)));
+            return findings;
+        }
+    };
+    let repo = vault.repo_path.clone();
+
+    // Unfinished rebase or merge, e.g. from a pull that failed
+    if let Some(operation) = git_repo.interrupted_operation() {
+        findings.push(Finding {
+            fix: Some(Fix::AbortOperation { repo: repo.clone(), operation }),
+            ..problem("repository", &format!("stuck in the middle of a {}", operation),
+                format!("A pull or restore stopped before finishing; backup and restore can't update the branch \
+                    until it is resolved. Aborting returns the vault to where it was before the {}.", operation))
+        });
+    }
+
+    // Remotes in the manifest against remotes in the git repository
+    match git_repo.remote_urls() {
+        Ok(git_remotes) => {
+            for (name, remote) in &vault.manifest.remotes {
+                let subject = format!("remote '{}'", name);
+                let set_remote = Fix::SetRemote { repo: repo.clone(), name: name.clone(), url: remote.url.clone() };
+                match git_remotes.get(name) {
+                    None => findings.push(Finding {
+                        fix: Some(set_remote),
+                        ..problem(&subject, "missing from the git repository",
+                            "The vault is configured to sync with it, but fetch and push can't find it.".to_string())
+                    }),
+                    Some(url) if url.as_deref() != Some(remote.url.as_str()) => findings.push(Finding {
+                        fix: Some(set_remote),
+                        ..problem(&subject, "URL differs from the vault configuration",
+                            format!("The vault uses {}, but git has {}.", remote.url, url.as_deref().unwrap_or("no URL")))
+                    }),
+                    Some(_) => {}
+                }
+            }
+            for (name, url) in &git_remotes {
+                if vault.manifest.remotes.contains_key(name) {
+                    continue;
+                }
+                let subject = format!("git remote '{}'", name);
+                let url = url.as_deref().unwrap_or("no URL");
+                if name == BUNDLE_REMOTE {
+                    // gfv's own name for applied bundles, never one the user set up
+                    findings.push(Finding {
+                        fix: Some(Fix::RemoveRemote { repo: repo.clone(), name: name.clone() }),
+                        ..problem(&subject, "left over from a bundle",
+                            format!("gfv uses this name for applied bundles, but the git repository has it as \
+                                a remote ({}). gfv doesn't sync with it.", url))
+                    });
+                } else {
+                    // Possibly added by hand (e.g. an upstream to compare with); not ours to delete
+                    findings.push(Finding {
+                        warning: true,
+                        ..problem(&subject, "not configured in the vault",
+                            format!("gfv doesn't sync with it ({}). Add it with 'gfv vault remote add {} <url>', \
+                                or remove it with 'git remote remove {}' in {} if it is left over.",
+                                url, name, name, display_path(&repo)))
+                    });
+                }
+            }
+        }
+        Err(e) => findings.push(problem("remotes", "cannot be read", format!("{:#}", e))),
+    }
+
+    // Manifest entries whose vault copy is gone
+    let mut entries: Vec<_> = vault.manifest.files.iter().collect();
+    entries.sort_by_key(|(vault_path, _)| vault_path.as_str());
+    for (vault_path, entry) in &entries {
+        if vault.get_file_path(vault_path).exists() {
+            continue;
+        }
+        let source_path = Path::new(&entry.source_path);
+        let mut finding = problem(vault_path, "missing from the vault repo", String::new());
+        if git_repo.head_has_path(vault_path) {
+            finding.explanation = "It was deleted from the vault repo after the last commit.".to_string();
+            finding.fix = Some(Fix::CheckoutEntry { repo: repo.clone(), vault_path: vault_path.to_string() });
+        } else if source_path.exists() {
+            finding.explanation = format!("It was never backed up. Run 'gfv backup' to copy {} into the vault.",
+                display_path(source_path));
+        } else {
+            finding.explanation = format!("Its source {} is gone as well. Stop managing it with 'gfv unlink {}'.",
+                display_path(source_path), vault_path);
+        }
+        findings.push(finding);
+    }
+
+    // Repo files no entry points to (another machine's files are normal, hence a warning)
+    let known = vault_support_files(&vault);
+    let mut walker = walkdir::WalkDir::new(&vault.repo_path).min_depth(1).sort_by_file_name().into_iter();
+    while let Some(item) = walker.next() {
+        let Ok(item) = item else { continue };
+        let Ok(relative) = item.path().strip_prefix(&vault.repo_path) else { continue };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        if relative == ".git" {
+            walker.skip_current_dir();
+            continue;
+        }
+        if entries.iter().any(|(vault_path, _)| is_within(&relative, vault_path)) || known.contains(&relative) {
+            if item.file_type().is_dir() {
+                walker.skip_current_dir();
+            }
+            continue;
+        }
+        // Descend into directories that hold entries
+        if item.file_type().is_dir()
+            && entries.iter().map(|(p, _)| p.as_str()).chain(known.iter().map(String::as_str))
+                .any(|vault_path| is_within(vault_path, &relative))
+        {
+            continue;
+        }
+        findings.push(Finding {
+            warning: true,
+            ..problem(&relative, "not used by any entry",
+                "Backup and restore ignore it. It may belong to another machine; otherwise map it with \
+                    'gfv adopt' or delete it from the vault repo.".to_string())
+        });
+        if item.file_type().is_dir() {
+            walker.skip_current_dir();
+        }
+    }
+
+    findings
+}
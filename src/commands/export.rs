@@ -0,0 +1,212 @@
+// Export command implementation - standalone bundles for machines without gfv
+
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use colored::Colorize;
+use flate2::{write::GzEncoder, Compression};
+use std::fs::File;
+use std::path::Path;
+
+use crate::catalog;
+use crate::vault::Vault;
+use super::helpers::{get_vault_dir, get_active_vault_name, resolve_source_path, select_entries, display_path};
+use super::link::is_sensitive_file;
+
+const VERSION: &str = env!("GFV_VERSION");
+
+/// Installer packed next to the files; `{header}` and `{entries}` are filled in
+const INSTALL_SCRIPT: &str = r#"#!/bin/sh
+{header}
+#
+# Usage: sh install.sh [--dry-run]
+#
+# Copies every file to its place. Existing files that differ are renamed to
+# <name>.gfv-backup-<timestamp> first; identical files are left alone.
+set -eu
+
+DRY_RUN=0
+case "${1:-}" in
+    -n|--dry-run) DRY_RUN=1 ;;
+    "") ;;
+    *) echo "usage: sh install.sh [--dry-run]" >&2; exit 2 ;;
+esac
+
+FILES=$(cd "$(dirname "$0")" && pwd)/files
+STAMP=$(date +%Y%m%d%H%M%S)
+installed=0
+unchanged=0
+backed_up=0
+
+same() {
+    if [ -d "$1" ]; then
+        [ -d "$2" ] && diff -r "$1" "$2" >/dev/null 2>&1
+    else
+        [ -f "$2" ] && cmp -s "$1" "$2"
+    fi
+}
+
+install_entry() {
+    src="$FILES/$1"
+    dest="$2"
+    if [ -e "$dest" ] || [ -L "$dest" ]; then
+        if same "$src" "$dest"; then
+            echo "  = $dest"
+            unchanged=$((unchanged + 1))
+            return 0
+        fi
+        echo "  ~ $dest (backup: $dest.gfv-backup-$STAMP)"
+        [ "$DRY_RUN" = 1 ] || mv "$dest" "$dest.gfv-backup-$STAMP"
+        backed_up=$((backed_up + 1))
+    else
+        echo "  + $dest"
+    fi
+    if [ "$DRY_RUN" = 0 ]; then
+        mkdir -p "$(dirname "$dest")"
+        cp -Rp "$src" "$dest"
+    fi
+    installed=$((installed + 1))
+}
+
+{entries}
+
+if [ "$DRY_RUN" = 1 ]; then
+    echo "Dry run: $installed to install ($backed_up to back up), $unchanged unchanged"
+else
+    echo "Installed $installed ($backed_up backed up), $unchanged unchanged"
+fi
+"#;
+
+pub fn export(
+    paths: Vec<String>,
+    bundle: String,
+    platform: Option<String>,
+    vault: Option<String>,
+) -> Result<()> {
+    // Get vault directory
+    let vault_name = vault.unwrap_or_else(get_active_vault_name);
+    let vault_dir = get_vault_dir(&vault_name)?;
+
+    // Check if vault is initialized
+    if !Vault::is_initialized(&vault_dir) {
+        bail!("Vault not initialized. Run 'gfv init' first.");
+    }
+
+    // Load vault
+    let vault = Vault::load(&vault_dir)
+        .context("Failed to load vault")?;
+
+    let platform = platform.unwrap_or_else(|| catalog::current_os().to_string());
+    if platform == "windows" {
+        bail!("Bundles install with a POSIX shell script; Windows is not supported");
+    }
+    if !["macos", "linux"].contains(&platform.as_str()) {
+        bail!("Unknown platform '{}'. Export for 'macos' or 'linux'", platform);
+    }
+    let home = dirs::home_dir()
+        .context("Failed to get home directory")?;
+
+    // Entries for the platform that have been backed up
+    let mut entries = Vec::new();
+    let mut other_platform = 0;
+    for vault_path in select_entries(&vault, &paths)? {
+        let entry = &vault.manifest.files[&vault_path];
+        if entry.platform.as_deref().is_some_and(|p| p != platform) {
+            other_platform += 1;
+            continue;
+        }
+        if !vault.get_file_path(&vault_path).exists() {
+            println!("  {} Skipping {} (not backed up yet; run 'gfv backup')", "⚠".yellow(), vault_path);
+            continue;
+        }
+        entries.push((vault_path, entry));
+    }
+    if entries.is_empty() {
+        bail!("Nothing to export for {}", platform);
+    }
+
+    println!("{} Exporting {} {} for {}", "==>".green().bold(), entries.len(),
+        if entries.len() == 1 { "entry" } else { "entries" }, platform);
+    if other_platform > 0 {
+        println!("  {} Left out {} {} for other platforms", "→".blue(), other_platform,
+            if other_platform == 1 { "entry" } else { "entries" });
+    }
+
+    // One line per entry: install_entry <vault path> <destination>
+    let mut lines = Vec::new();
+    let mut sensitive = 0;
+    for (vault_path, entry) in &entries {
+        let source_path = Path::new(&entry.source_path);
+        let marker = if entry.sensitive || is_sensitive_file(source_path) {
+            sensitive += 1;
+            format!(" ({})", "sensitive".yellow())
+        } else {
+            String::new()
+        };
+        println!("  {} {} → {}{}", "✓".green(), vault_path, display_path(source_path), marker);
+        lines.push(format!("install_entry {} {}", shell_quote(vault_path), install_destination(source_path, &home)));
+    }
+    if sensitive > 0 {
+        println!("\n{} The bundle contains {} potentially sensitive {}; store and share it accordingly",
+            "Warning:".yellow().bold(), sensitive, if sensitive == 1 { "entry" } else { "entries" });
+    }
+
+    let header = format!("# Installs {} {} from the gfv vault '{}' ({}).\n# Generated by gfv {} on {}.",
+        entries.len(), if entries.len() == 1 { "entry" } else { "entries" },
+        vault_name, platform, VERSION, Utc::now().format("%Y-%m-%d %H:%M UTC"));
+    let script = INSTALL_SCRIPT
+        .replace("{header}", &header)
+        .replace("{entries}", &lines.join("\n"));
+
+    // <root>/install.sh and <root>/files/<vault path>
+    let root = format!("gfv-{}", vault_name);
+    let bundle_path = resolve_source_path(&bundle)?;
+    let file = File::create(&bundle_path)
+        .with_context(|| format!("Failed to create {}", bundle_path.display()))?;
+    let mut tar = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    tar.follow_symlinks(false);
+
+    let mut header = tar::Header::new_gnu();
+    header.set_size(script.len() as u64);
+    header.set_mode(0o755);
+    header.set_mtime(Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    tar.append_data(&mut header, format!("{}/install.sh", root), script.as_bytes())
+        .context("Failed to write install.sh")?;
+
+    for (vault_path, _) in &entries {
+        let path = vault.get_file_path(vault_path);
+        let name = format!("{}/files/{}", root, vault_path);
+        if path.is_dir() {
+            tar.append_dir_all(&name, &path)
+        } else {
+            tar.append_path_with_name(&path, &name)
+        }
+        .with_context(|| format!("Failed to add {}", vault_path))?;
+    }
+
+    tar.into_inner()
+        .and_then(|encoder| encoder.finish())
+        .context("Failed to write bundle")?;
+
+    let size = std::fs::metadata(&bundle_path).map(|m| m.len()).unwrap_or(0);
+    println!("\n{} Wrote {} ({} KB)", "✓".green().bold(), bundle_path.display(), size.div_ceil(1024));
+    println!("\nInstall on another machine with:");
+    println!("  tar xzf {} && sh {}/install.sh",
+        bundle_path.file_name().unwrap_or_default().to_string_lossy(), root);
+
+    Ok(())
+}
+
+/// Shell word for where an entry is installed: `"$HOME"/'...'` under the home
+/// directory, so the bundle works for any user, or the absolute path
+fn install_destination(source_path: &Path, home: &Path) -> String {
+    match source_path.strip_prefix(home) {
+        Ok(relative) => format!("\"$HOME\"/{}", shell_quote(&relative.to_string_lossy())),
+        Err(_) => shell_quote(&source_path.to_string_lossy()),
+    }
+}
+
+/// Single-quote a string for POSIX sh
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
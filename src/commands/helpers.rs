@@ -1,8 +1,12 @@
 // Helper functions for vault operations
 
-use anyhow::{Context, Result};
-use std::path::PathBuf;
-use crate::config::Config;
+use anyhow::{bail, Context, Result};
+use globset::Glob;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::config::{self, settings::SHARED_CONFIG_FILE, Config};
+use crate::vault::Vault;
+pub use crate::vault::manifest::is_within;
 
 /// Get the vault directory path by name
 /// If name is provided, use that specific vault
@@ -17,9 +21,7 @@ pub fn get_vault_dir(vault_name: &str) -> Result<PathBuf> {
 
     // If vault doesn't exist in config, check if it's "default" and use default path
     if vault_name == "default" && config.vaults.is_empty() {
-        let home = dirs::home_dir()
-            .context("Failed to get home directory")?;
-        return Ok(home.join(".gfv").join("default"));
+        return Ok(config::gfv_home()?.join("default"));
     }
 
     Err(anyhow::anyhow!("Vault '{}' not found", vault_name))
@@ -39,3 +41,134 @@ pub fn get_active_vault_name() -> String {
         })
         .unwrap_or_else(|| "default".to_string())
 }
+
+/// Resolve a source path given on the command line: expand `~` and make it absolute
+pub fn resolve_source_path(source: &str) -> Result<PathBuf> {
+    let expanded = match (source.strip_prefix('~'), dirs::home_dir()) {
+        (Some(""), Some(home)) => home,
+        (Some(rest), Some(home)) if rest.starts_with('/') => home.join(&rest[1..]),
+        _ => PathBuf::from(source),
+    };
+
+    if expanded.is_absolute() {
+        Ok(expanded)
+    } else {
+        Ok(std::env::current_dir()?.join(Path::new(&expanded)))
+    }
+}
+
+/// `~/...` for paths under the home directory
+pub fn display_path(path: &Path) -> String {
+    match dirs::home_dir().and_then(|home| path.strip_prefix(home).ok().map(Path::to_path_buf)) {
+        Some(relative) => format!("~/{}", relative.display()),
+        None => path.display().to_string(),
+    }
+}
+
+/// Vault paths chosen by `gfv backup [paths]` and `gfv export [paths]`, sorted. Each argument
+/// is a vault path, a glob over vault paths (`nvim/*`, `*.toml`) or the source path of an entry.
+/// Without arguments every entry is selected.
+pub fn select_entries(vault: &Vault, patterns: &[String]) -> Result<Vec<String>> {
+    let mut selected: Vec<String> = if patterns.is_empty() {
+        vault.manifest.files.keys().cloned().collect()
+    } else {
+        let mut selected = Vec::new();
+        for pattern in patterns {
+            let trimmed = pattern.trim_end_matches('/');
+            let glob = Glob::new(trimmed)
+                .with_context(|| format!("Invalid pattern: {}", pattern))?
+                .compile_matcher();
+            let source = resolve_source_path(trimmed)?;
+
+            let matches: Vec<&String> = vault.manifest.files.iter()
+                .filter(|(vault_path, entry)| glob.is_match(vault_path.as_str()) || Path::new(&entry.source_path) == source)
+                .map(|(vault_path, _)| vault_path)
+                .collect();
+            if matches.is_empty() {
+                bail!("No linked file matches '{}'\n\nList managed files with: gfv list", pattern);
+            }
+            selected.extend(matches.into_iter().cloned());
+        }
+        selected
+    };
+
+    selected.sort();
+    selected.dedup();
+    Ok(selected)
+}
+
+/// Files in the vault repo that belong to the vault itself rather than to an entry
+pub fn vault_support_files(vault: &Vault) -> Vec<String> {
+    let mut files = vec![".gitignore".to_string(), SHARED_CONFIG_FILE.to_string()];
+    if let Some(prompt_file) = vault.settings.ai.prompt_file.as_deref() {
+        files.push(prompt_file.to_string());
+    }
+    files
+}
+
+/// Remove the directories above `path` that became empty, up to (not including) `root`
+pub fn remove_empty_parents(path: &Path, root: &Path) {
+    let mut dir = path.parent();
+    while let Some(current) = dir {
+        if current == root || !current.starts_with(root) || fs::remove_dir(current).is_err() {
+            break;
+        }
+        dir = current.parent();
+    }
+}
+
+/// Copy a directory tree (files, directories and symlinks), then check that
+/// every file arrived with the same size
+pub fn copy_tree(src: &Path, dst: &Path) -> Result<()> {
+    let mut copied = 0u64;
+    for item in walkdir::WalkDir::new(src) {
+        let item = item?;
+        let target = dst.join(item.path().strip_prefix(src)?);
+        let file_type = item.file_type();
+        if file_type.is_dir() {
+            fs::create_dir_all(&target)?;
+        } else if file_type.is_symlink() {
+            let link = fs::read_link(item.path())?;
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(&link, &target)?;
+            #[cfg(windows)]
+            fs::copy(item.path(), &target)?;
+        } else {
+            let size = fs::copy(item.path(), &target)
+                .with_context(|| format!("Failed to copy {}", item.path().display()))?;
+            if size != item.metadata()?.len() {
+                bail!("Size mismatch after copying {}", item.path().display());
+            }
+            copied += 1;
+        }
+    }
+
+    let arrived = walkdir::WalkDir::new(dst).into_iter()
+        .flatten()
+        .filter(|item| item.file_type().is_file())
+        .count() as u64;
+    if arrived != copied {
+        bail!("Copied {} files but found {} at the destination", copied, arrived);
+    }
+    Ok(())
+}
+
+/// Files in the vault repo that no entry points to, sorted
+pub fn unmapped_files(vault: &Vault) -> Vec<String> {
+    let support = vault_support_files(vault);
+    let mut files: Vec<String> = walkdir::WalkDir::new(&vault.repo_path)
+        .min_depth(1)
+        .into_iter()
+        .filter_entry(|item| item.file_name() != ".git")
+        .flatten()
+        .filter(|item| item.file_type().is_file())
+        .filter_map(|item| {
+            let relative = item.path().strip_prefix(&vault.repo_path).ok()?;
+            Some(relative.to_string_lossy().replace('\\', "/"))
+        })
+        .filter(|path| !support.contains(path))
+        .filter(|path| !vault.manifest.files.keys().any(|entry| is_within(path, entry)))
+        .collect();
+    files.sort();
+    files
+}
@@ -0,0 +1,315 @@
+// Import command implementation - adopts dotfiles managed by other tools
+
+mod bare;
+mod chezmoi;
+mod stow;
+
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use clap::ValueEnum;
+use colored::Colorize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::git_ops::GitRepo;
+use crate::vault::{Vault, manifest::FileEntry};
+use super::helpers::{get_vault_dir, get_active_vault_name, resolve_source_path, display_path};
+use super::link::{infer_vault_path, read_yes};
+
+/// Tools whose layout `gfv import` understands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ImportFrom {
+    /// GNU stow directory with one package per subdirectory
+    Stow,
+    /// Bare git repository with $HOME as work tree
+    Bare,
+    /// yadm repository (a bare repository with alternate files)
+    Yadm,
+    /// chezmoi source directory
+    Chezmoi,
+}
+
+impl ImportFrom {
+    fn name(self) -> &'static str {
+        match self {
+            ImportFrom::Stow => "stow",
+            ImportFrom::Bare => "bare repo",
+            ImportFrom::Yadm => "yadm",
+            ImportFrom::Chezmoi => "chezmoi",
+        }
+    }
+}
+
+/// Where the content of an imported file comes from
+pub enum Content {
+    File(PathBuf),
+    Blob(Vec<u8>),
+}
+
+/// A file found in another tool's layout
+pub struct ImportItem {
+    /// Path in the tool's layout, shown in the preview
+    pub origin: String,
+    /// Where the file is deployed (the source path of the manifest entry)
+    pub source_path: PathBuf,
+    pub content: Content,
+    pub platform: Option<String>,
+    pub sensitive: bool,
+    /// Shown in the preview, e.g. for rendered templates
+    pub note: Option<String>,
+}
+
+/// Everything a tool's layout has to offer
+pub struct Layout {
+    /// Directory the files are deployed to
+    pub root: PathBuf,
+    pub items: Vec<ImportItem>,
+    /// Files that cannot be imported, with the reason
+    pub skipped: Vec<(String, String)>,
+    /// Things the user should know, e.g. ignored tool settings
+    pub notes: Vec<String>,
+}
+
+/// An item with its vault path
+struct ImportPlan<'a> {
+    item: &'a ImportItem,
+    vault_path: String,
+}
+
+pub fn import(
+    from: ImportFrom,
+    path: Option<String>,
+    target: Option<String>,
+    no_history: bool,
+    dry_run: bool,
+    yes: bool,
+    vault: Option<String>,
+) -> Result<()> {
+    // Get vault directory
+    let vault_name = vault.unwrap_or_else(get_active_vault_name);
+    let vault_dir = get_vault_dir(&vault_name)?;
+
+    // Check if vault is initialized
+    if !Vault::is_initialized(&vault_dir) {
+        bail!("Vault not initialized. Run 'gfv init' first.");
+    }
+
+    // Load vault
+    let mut vault = Vault::load(&vault_dir)
+        .context("Failed to load vault")?;
+
+    let home = dirs::home_dir()
+        .context("Failed to get home directory")?;
+    let path = match path {
+        Some(path) => resolve_source_path(&path)?,
+        None => default_path(from, &home)?,
+    };
+    if !path.exists() {
+        bail!("{} not found", path.display());
+    }
+    let target = target.map(|target| resolve_source_path(&target)).transpose()?;
+
+    let layout = match from {
+        ImportFrom::Stow => {
+            // Like stow, deploy into the parent of the stow directory by default
+            let target = target.or_else(|| path.parent().map(Path::to_path_buf))
+                .context("Stow directory has no parent; use --target")?;
+            stow::read(&path, &target)?
+        }
+        ImportFrom::Bare | ImportFrom::Yadm => bare::read(&path, target.as_deref(), &home, from == ImportFrom::Yadm)?,
+        ImportFrom::Chezmoi => chezmoi::read(&path, target.as_deref().unwrap_or(&home))?,
+    };
+
+    println!("{} Importing from {} ({})", "==>".green().bold(), from.name(), display_path(&path));
+
+    let (plans, managed) = plan_import(&vault, &layout)?;
+    preview(&plans, &managed, &layout);
+
+    if plans.is_empty() {
+        println!("\n{} Nothing to import", "✓".green());
+        return Ok(());
+    }
+    if dry_run {
+        println!("\n{} Dry run: nothing was imported", "→".blue());
+        return Ok(());
+    }
+    if !yes {
+        println!("\nImport {}? (y/N)", file_count(plans.len()));
+        if !read_yes()? {
+            println!("Cancelled.");
+            return Ok(());
+        }
+    }
+
+    let git_repo = GitRepo::open(&vault.repo_path)
+        .context("Failed to open git repository")?;
+    let message = format!("Import {} from {}", file_count(plans.len()), from.name());
+
+    // A bare repository's commits are replayed with vault paths; other tools have no history to keep
+    let with_history = matches!(from, ImportFrom::Bare | ImportFrom::Yadm) && !no_history;
+    if with_history {
+        if git_repo.has_changes()? {
+            bail!("The vault has uncommitted changes. Run 'gfv backup' first, or import with --no-history");
+        }
+        let map = |repo_path: &str| {
+            bare::target(repo_path, from == ImportFrom::Yadm)
+                .and_then(|target| infer_vault_path(&layout.root.join(target)).ok())
+        };
+        match git_repo.import_history(&path, &map)? {
+            Some(history) => {
+                git_repo.merge_imported(&history, &message)?;
+                println!("  {} Imported {} commits of history", "✓".green(), history.commits);
+            }
+            None => println!("  {} The repository has no commits", "→".blue()),
+        }
+    } else {
+        for plan in &plans {
+            write_file(&vault.get_file_path(&plan.vault_path), &plan.item.content)
+                .with_context(|| format!("Failed to copy {}", plan.item.origin))?;
+        }
+        let pathspecs: Vec<&str> = plans.iter().map(|plan| plan.vault_path.as_str()).collect();
+        git_repo.stage_paths(&pathspecs)
+            .context("Failed to stage changes")?;
+        if git_repo.has_staged_changes()? {
+            git_repo.commit(&message)
+                .context("Failed to commit changes")?;
+            println!("  {} Committed locally: \"{}\"", "✓".green(), message);
+        }
+    }
+
+    // Manifest entries, saved once for the whole import
+    for plan in &plans {
+        let entry = FileEntry {
+            source_path: plan.item.source_path.display().to_string(),
+            file_type: "file".to_string(),
+            platform: plan.item.platform.clone(),
+            added_at: Utc::now(),
+            last_sync: None,
+            sensitive: plan.item.sensitive,
+        };
+        vault.manifest.add_file(plan.vault_path.clone(), entry);
+    }
+    vault.save_manifest()
+        .context("Failed to save manifest")?;
+    println!("  {} Updated manifest", "✓".green());
+
+    println!("\n{} {} now managed by gfv. Use 'gfv status' to compare with your home directory.",
+        "✓".green().bold(), if plans.len() == 1 { "1 file is".to_string() } else { format!("{} files are", plans.len()) });
+    if from == ImportFrom::Stow {
+        println!("Files deployed by stow are still symlinks into {}.", display_path(&path));
+        println!("Run 'stow -D' for each package, then 'gfv restore' to replace them with real files.");
+    }
+
+    Ok(())
+}
+
+/// Default location of the tool's repository or source directory
+fn default_path(from: ImportFrom, home: &Path) -> Result<PathBuf> {
+    let candidates: &[&str] = match from {
+        ImportFrom::Chezmoi => &[".local/share/chezmoi"],
+        ImportFrom::Yadm => &[".local/share/yadm/repo.git", ".config/yadm/repo.git", ".yadm/repo.git"],
+        ImportFrom::Stow => bail!("Give the stow directory, e.g. gfv import --from stow ~/dotfiles"),
+        ImportFrom::Bare => bail!("Give the bare repository, e.g. gfv import --from bare ~/.dotfiles"),
+    };
+    candidates.iter()
+        .map(|candidate| home.join(candidate))
+        .find(|path| path.exists())
+        .with_context(|| format!("No {} directory found at ~/{}; give its path", from.name(), candidates[0]))
+}
+
+/// Vault paths for every item. Files already linked from the same place are left
+/// out; anything that would overwrite another entry fails the whole import.
+fn plan_import<'a>(vault: &Vault, layout: &'a Layout) -> Result<(Vec<ImportPlan<'a>>, Vec<ImportPlan<'a>>)> {
+    let mut plans: Vec<ImportPlan> = Vec::new();
+    let mut managed = Vec::new();
+    let mut problems = Vec::new();
+    let mut seen: HashMap<String, &ImportItem> = HashMap::new();
+
+    for item in &layout.items {
+        let vault_path = infer_vault_path(&item.source_path)?;
+
+        if let Some(other) = seen.insert(vault_path.clone(), item) {
+            problems.push(format!("{} and {} would both be stored as {}", other.origin, item.origin, vault_path));
+            continue;
+        }
+        match vault.manifest.get_file(&vault_path) {
+            Some(existing) if Path::new(&existing.source_path) == item.source_path => {
+                managed.push(ImportPlan { item, vault_path });
+            }
+            Some(existing) => {
+                problems.push(format!("{} → {} is already managed (linked from {})",
+                    item.origin, vault_path, existing.source_path));
+            }
+            None => plans.push(ImportPlan { item, vault_path }),
+        }
+    }
+
+    if !problems.is_empty() {
+        let mut message = format!("Nothing was imported: {} cannot be imported", file_count(problems.len()));
+        for problem in &problems {
+            message.push_str(&format!("\n  - {}", problem));
+        }
+        message.push_str("\n\nUnlink the conflicting entries, or link these files one by one with 'gfv link --name'");
+        bail!(message);
+    }
+
+    plans.sort_by(|a, b| a.vault_path.cmp(&b.vault_path));
+    Ok((plans, managed))
+}
+
+fn preview(plans: &[ImportPlan], managed: &[ImportPlan], layout: &Layout) {
+    if !plans.is_empty() {
+        let origin_width = plans.iter().map(|plan| plan.item.origin.len()).max().unwrap_or(0).max("FROM".len());
+        let vault_width = plans.iter().map(|plan| plan.vault_path.len()).max().unwrap_or(0).max("VAULT PATH".len());
+
+        println!("\n  {:<ow$}  {:<vw$}  SOURCE", "FROM", "VAULT PATH", ow = origin_width, vw = vault_width);
+        for plan in plans {
+            let mut notes = Vec::new();
+            if let Some(ref platform) = plan.item.platform {
+                notes.push(platform.clone());
+            }
+            if plan.item.sensitive {
+                notes.push("sensitive".yellow().to_string());
+            }
+            if let Some(ref note) = plan.item.note {
+                notes.push(note.clone());
+            }
+            let notes = if notes.is_empty() { String::new() } else { format!("  ({})", notes.join(", ")) };
+            println!("  {:<ow$}  {:<vw$}  {}{}", plan.item.origin, plan.vault_path,
+                display_path(&plan.item.source_path), notes, ow = origin_width, vw = vault_width);
+        }
+    }
+
+    if !managed.is_empty() {
+        println!("\n  {} {} already managed: {}", "→".blue(), managed.len(),
+            managed.iter().map(|plan| plan.vault_path.as_str()).collect::<Vec<_>>().join(", "));
+    }
+    if !layout.skipped.is_empty() {
+        println!("\n  {} Skipped:", "→".blue());
+        for (origin, reason) in &layout.skipped {
+            println!("      {} ({})", origin, reason);
+        }
+    }
+    for note in &layout.notes {
+        println!("  {} {}", "⚠".yellow(), note);
+    }
+}
+
+fn write_file(destination: &Path, content: &Content) -> Result<()> {
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    match content {
+        Content::File(source) => {
+            fs::copy(source, destination)?;
+        }
+        Content::Blob(bytes) => fs::write(destination, bytes)?,
+    }
+    Ok(())
+}
+
+/// `1 file`, `3 files`
+fn file_count(count: usize) -> String {
+    format!("{} {}", count, if count == 1 { "file" } else { "files" })
+}
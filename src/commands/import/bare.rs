@@ -0,0 +1,140 @@
+// Bare repositories (git --git-dir=~/.dotfiles --work-tree=~) and yadm
+
+use anyhow::{Context, Result};
+use git2::Repository;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use super::{Content, ImportItem, Layout};
+use crate::catalog;
+use crate::commands::link::is_sensitive_file;
+
+/// yadm's own files in the work tree
+const YADM_DIRS: [&str; 3] = [".config/yadm/", ".local/share/yadm/", ".yadm/"];
+
+/// Where a repository path is deployed
+enum Resolved {
+    /// Path relative to the work tree, and the OS it was chosen for
+    Target(String, Option<String>),
+    Skip(&'static str),
+}
+
+/// Work-tree path of a repository path, or `None` if it is not imported
+/// (yadm settings, alternates for other systems)
+pub fn target(repo_path: &str, yadm: bool) -> Option<String> {
+    match resolve(repo_path, yadm) {
+        Resolved::Target(path, _) => Some(path),
+        Resolved::Skip(_) => None,
+    }
+}
+
+fn resolve(repo_path: &str, yadm: bool) -> Resolved {
+    if yadm && YADM_DIRS.iter().any(|dir| repo_path.starts_with(dir)) {
+        return Resolved::Skip("yadm settings");
+    }
+    if !yadm || !repo_path.contains("##") {
+        return Resolved::Target(repo_path.to_string(), None);
+    }
+
+    // Alternates: name##default, name##os.Linux (on a file or a directory)
+    let mut platform = None;
+    let mut parts = Vec::new();
+    for part in repo_path.split('/') {
+        let Some((base, conditions)) = part.split_once("##") else {
+            parts.push(part);
+            continue;
+        };
+        for condition in conditions.split(',') {
+            match condition.split_once('.') {
+                None if matches!(condition, "default" | "d") => {}
+                Some(("os" | "o", os)) if os.eq_ignore_ascii_case(yadm_os()) => {
+                    platform = Some(catalog::current_os().to_string());
+                }
+                Some(("os" | "o", _)) => return Resolved::Skip("alternate for another OS"),
+                _ => return Resolved::Skip("yadm alternate condition not supported"),
+            }
+        }
+        parts.push(base);
+    }
+    Resolved::Target(parts.join("/"), platform)
+}
+
+/// This OS as yadm names it (`uname -s`)
+fn yadm_os() -> &'static str {
+    match catalog::current_os() {
+        "macos" => "Darwin",
+        "linux" => "Linux",
+        other => other,
+    }
+}
+
+/// Read the files at HEAD of a bare repository, deployed into its work tree:
+/// `target`, else `core.worktree`, else the home directory
+pub fn read(repo_path: &Path, target: Option<&Path>, home: &Path, yadm: bool) -> Result<Layout> {
+    let repo = Repository::open(repo_path)
+        .with_context(|| format!("Failed to open repository {}", repo_path.display()))?;
+
+    let root = match target {
+        Some(target) => target.to_path_buf(),
+        None => repo.config()?.get_path("core.worktree").unwrap_or_else(|_| home.to_path_buf()),
+    };
+    let mut layout = Layout {
+        root: root.clone(),
+        items: Vec::new(),
+        skipped: Vec::new(),
+        notes: Vec::new(),
+    };
+
+    let Ok(head) = repo.head() else {
+        return Ok(layout);
+    };
+    let tree = head.peel_to_tree().context("Failed to read HEAD")?;
+
+    // Tree order puts name##default before name##os.X, so the OS alternate comes last
+    let mut found: Vec<(String, String, Option<String>, Vec<u8>)> = Vec::new();
+    let mut walk_error = None;
+    let walked = tree.walk(git2::TreeWalkMode::PreOrder, |dir, item| {
+        let path = format!("{}{}", dir, item.name().unwrap_or_default());
+        match (item.kind(), item.filemode()) {
+            (Some(git2::ObjectType::Blob), 0o120000) => layout.skipped.push((path, "symlink".to_string())),
+            (Some(git2::ObjectType::Blob), _) => match resolve(&path, yadm) {
+                Resolved::Skip(reason) => layout.skipped.push((path, reason.to_string())),
+                Resolved::Target(deployed, platform) => match repo.find_blob(item.id()) {
+                    Ok(blob) => found.push((path, deployed, platform, blob.content().to_vec())),
+                    Err(e) => {
+                        walk_error = Some(e);
+                        return git2::TreeWalkResult::Abort;
+                    }
+                },
+            },
+            (Some(git2::ObjectType::Commit), _) => layout.skipped.push((path, "submodule".to_string())),
+            _ => {}
+        }
+        git2::TreeWalkResult::Ok
+    });
+    if let Some(e) = walk_error {
+        return Err(e).context("Failed to read files from the repository");
+    }
+    walked?;
+
+    // One file per deployed path: the last alternate wins
+    let mut taken = HashSet::new();
+    for (origin, deployed, platform, content) in found.into_iter().rev() {
+        if !taken.insert(deployed.clone()) {
+            layout.skipped.push((origin, "another alternate is used".to_string()));
+            continue;
+        }
+        let source_path: PathBuf = root.join(&deployed);
+        layout.items.push(ImportItem {
+            origin,
+            sensitive: is_sensitive_file(&source_path),
+            source_path,
+            content: Content::Blob(content),
+            platform,
+            note: None,
+        });
+    }
+    layout.items.reverse();
+
+    Ok(layout)
+}
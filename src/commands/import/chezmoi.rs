@@ -0,0 +1,161 @@
+// chezmoi: a source directory whose names carry attributes (dot_, private_, ...)
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{Content, ImportItem, Layout};
+use crate::commands::link::is_sensitive_file;
+
+/// Name prefixes chezmoi strips from files and directories, all of them attributes
+const ATTRIBUTES: [&str; 15] = [
+    "create_", "modify_", "remove_", "run_", "symlink_", "once_", "onchange_", "before_", "after_",
+    "encrypted_", "private_", "readonly_", "empty_", "executable_", "exact_",
+];
+
+/// A source name with its attributes parsed
+struct SourceName {
+    /// Name in the target directory
+    target: String,
+    attributes: Vec<&'static str>,
+    template: bool,
+}
+
+impl SourceName {
+    fn parse(name: &str) -> Self {
+        let mut rest = name;
+        let mut attributes = Vec::new();
+        let mut literal = false;
+
+        loop {
+            if let Some(stripped) = rest.strip_prefix("literal_") {
+                rest = stripped;
+                literal = true;
+                break;
+            }
+            match ATTRIBUTES.iter().find(|attribute| rest.starts_with(*attribute)) {
+                Some(attribute) => {
+                    attributes.push(*attribute);
+                    rest = &rest[attribute.len()..];
+                }
+                None => break,
+            }
+        }
+
+        let target = match rest.strip_prefix("dot_") {
+            Some(stripped) if !literal => format!(".{}", stripped),
+            _ => rest.to_string(),
+        };
+
+        // Suffixes: .literal stops parsing, .tmpl marks a template
+        let (target, template) = if let Some(stripped) = target.strip_suffix(".literal") {
+            (stripped.to_string(), false)
+        } else if let Some(stripped) = target.strip_suffix(".tmpl") {
+            (stripped.to_string(), true)
+        } else {
+            (target, false)
+        };
+
+        Self { target, attributes, template }
+    }
+
+    fn has(&self, attribute: &str) -> bool {
+        self.attributes.contains(&attribute)
+    }
+
+    /// Why a file with these attributes is not a plain file to import
+    fn unsupported(&self) -> Option<&'static str> {
+        if self.has("run_") {
+            Some("script")
+        } else if self.has("modify_") {
+            Some("modify script")
+        } else if self.has("remove_") {
+            Some("removal")
+        } else if self.has("symlink_") {
+            Some("symlink")
+        } else if self.has("encrypted_") {
+            Some("encrypted")
+        } else {
+            None
+        }
+    }
+}
+
+/// Read a chezmoi source directory, deployed into `target`
+pub fn read(source_dir: &Path, target: &Path) -> Result<Layout> {
+    let mut layout = Layout {
+        root: target.to_path_buf(),
+        items: Vec::new(),
+        skipped: Vec::new(),
+        notes: Vec::new(),
+    };
+
+    // .chezmoiroot moves the source state into a subdirectory
+    let mut root = source_dir.to_path_buf();
+    if let Ok(subdir) = fs::read_to_string(source_dir.join(".chezmoiroot")) {
+        root = root.join(subdir.trim());
+    }
+    if root.join(".chezmoiignore").exists() {
+        layout.notes.push(".chezmoiignore was not applied; leave out files you don't want with 'gfv unlink'".to_string());
+    }
+
+    read_dir(&root, Path::new(""), target, false, &mut layout)
+        .with_context(|| format!("Failed to read chezmoi source directory {}", root.display()))?;
+    Ok(layout)
+}
+
+/// Add the files below `dir`; `origin` is its path in the source directory
+fn read_dir(dir: &Path, origin: &Path, target: &Path, private: bool, layout: &mut Layout) -> Result<()> {
+    let mut entries: Vec<_> = fs::read_dir(dir)?.flatten().collect();
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        // chezmoi ignores every other source name starting with a dot
+        if name.starts_with('.') {
+            continue;
+        }
+
+        let path = entry.path();
+        let origin = origin.join(&name);
+        let parsed = SourceName::parse(&name);
+        let private = private || parsed.has("private_");
+
+        if entry.file_type()?.is_dir() {
+            if parsed.has("remove_") {
+                layout.skipped.push((origin.display().to_string(), "removal".to_string()));
+                continue;
+            }
+            read_dir(&path, &origin, &target.join(&parsed.target), private, layout)?;
+            continue;
+        }
+
+        if let Some(reason) = parsed.unsupported() {
+            layout.skipped.push((origin.display().to_string(), reason.to_string()));
+            continue;
+        }
+
+        let source_path = target.join(&parsed.target);
+        let (content, note) = if parsed.template {
+            // Only the rendered file on this machine has real content
+            if !source_path.is_file() {
+                layout.skipped.push((origin.display().to_string(), "template not applied here".to_string()));
+                continue;
+            }
+            (Content::File(source_path.clone()), Some("template, copied as rendered".to_string()))
+        } else {
+            (Content::File(PathBuf::from(&path)), None)
+        };
+
+        layout.items.push(ImportItem {
+            origin: origin.display().to_string(),
+            sensitive: private || is_sensitive_file(&source_path),
+            source_path,
+            content,
+            platform: None,
+            note,
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+// GNU stow: one package per subdirectory, mirrored into the target directory
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{Content, ImportItem, Layout};
+use crate::commands::link::is_sensitive_file;
+
+/// Names stow ignores anywhere in a package (its default ignore list)
+const IGNORED: [&str; 10] = [".git", ".gitignore", ".gitmodules", ".svn", ".hg", "CVS", "RCS", "_darcs", ".cvsignore", ".stow-local-ignore"];
+
+/// Prefixes stow ignores at the top of a package
+const IGNORED_TOP: [&str; 3] = ["README", "LICENSE", "COPYING"];
+
+/// Read every package in `stow_dir`, deployed into `target`
+pub fn read(stow_dir: &Path, target: &Path) -> Result<Layout> {
+    let mut layout = Layout {
+        root: target.to_path_buf(),
+        items: Vec::new(),
+        skipped: Vec::new(),
+        notes: Vec::new(),
+    };
+
+    let mut packages: Vec<PathBuf> = fs::read_dir(stow_dir)
+        .with_context(|| format!("Failed to read stow directory {}", stow_dir.display()))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir() && !is_hidden(path))
+        .collect();
+    packages.sort();
+
+    for package in &packages {
+        let name = package.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        if package.join(".stow-local-ignore").exists() {
+            layout.notes.push(format!(
+                "{} has a .stow-local-ignore; only stow's default ignore list was applied", name));
+        }
+        read_dir(package, Path::new(""), &name, target, &mut layout)?;
+    }
+
+    Ok(layout)
+}
+
+/// Add the files below `dir`; `relative` is its path inside the package
+fn read_dir(dir: &Path, relative: &Path, package: &str, target: &Path, layout: &mut Layout) -> Result<()> {
+    let mut entries: Vec<_> = fs::read_dir(dir)?.flatten().collect();
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let top = relative.as_os_str().is_empty();
+        if is_ignored(&name, top) {
+            continue;
+        }
+
+        let path = entry.path();
+        let origin = format!("{}/{}", package, relative.join(&name).display());
+        let file_type = entry.file_type()?;
+
+        if file_type.is_symlink() {
+            layout.skipped.push((origin, "symlink".to_string()));
+        } else if file_type.is_dir() {
+            read_dir(&path, &relative.join(&name), package, target, layout)?;
+        } else {
+            // `stow --dotfiles` deploys dot-foo as .foo
+            let deployed: PathBuf = relative.join(&name).components()
+                .map(|part| {
+                    let part = part.as_os_str().to_string_lossy();
+                    match part.strip_prefix("dot-") {
+                        Some(rest) => format!(".{}", rest),
+                        None => part.into_owned(),
+                    }
+                })
+                .collect();
+            let source_path = target.join(deployed);
+            layout.items.push(ImportItem {
+                origin,
+                sensitive: is_sensitive_file(&source_path),
+                source_path,
+                content: Content::File(path),
+                platform: None,
+                note: None,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.'))
+}
+
+fn is_ignored(name: &str, top: bool) -> bool {
+    IGNORED.contains(&name)
+        || name.ends_with('~')
+        || (name.starts_with('#') && name.ends_with('#'))
+        || (top && IGNORED_TOP.iter().any(|prefix| name.starts_with(prefix)))
+}
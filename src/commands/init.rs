@@ -8,8 +8,10 @@ pub fn init(
     remote: Option<String>,
     branch: Option<String>,
     name: String,
+    ssh_key: Option<String>,
+    token_env: Option<String>,
     _no_sync: bool,
 ) -> Result<()> {
     // Simply delegate to vault create
-    crate::commands::vault::create(name, path, remote, branch)
+    crate::commands::vault::create(name, path, remote, branch, ssh_key, token_env)
 }
@@ -2,16 +2,37 @@
 
 use anyhow::{bail, Context, Result};
 use colored::Colorize;
-use std::path::{Path, PathBuf};
 use chrono::Utc;
+use globset::GlobBuilder;
+use std::path::{Path, PathBuf};
 
+use crate::catalog;
 use crate::vault::{Vault, manifest::FileEntry};
-use super::helpers::{get_vault_dir, get_active_vault_name};
+use super::helpers::{get_vault_dir, get_active_vault_name, resolve_source_path};
+
+/// A source path to link, with an optional vault path (inferred otherwise)
+pub struct LinkRequest {
+    pub source_path: PathBuf,
+    pub vault_path: Option<String>,
+    pub platform: Option<String>,
+}
+
+/// A source path about to be linked
+struct LinkPlan {
+    source_path: PathBuf,
+    vault_path: String,
+    platform: Option<String>,
+    file_type: &'static str,
+    exists_locally: bool,
+    exists_in_vault: bool,
+    sensitive: bool,
+}
 
 pub fn link(
-    source: String,
+    sources: Vec<String>,
     name: Option<String>,
     platform: Option<String>,
+    yes: bool,
     vault: Option<String>,
 ) -> Result<()> {
     // Get vault directory
@@ -27,127 +48,290 @@ pub fn link(
     let mut vault = Vault::load(&vault_dir)
         .context("Failed to load vault")?;
 
-    // Validate and resolve source path
-    // First expand ~ in the string before creating PathBuf
-    let source = if source.starts_with("~/") || source == "~" {
-        if let Some(home) = dirs::home_dir() {
-            if source == "~" {
-                home.display().to_string()
-            } else {
-                home.join(&source[2..]).display().to_string()
-            }
-        } else {
-            source
-        }
-    } else {
-        source
-    };
+    // Resolve sources (expand ~ and globs, make absolute)
+    let source_paths = expand_sources(&sources)?;
+    if name.is_some() && source_paths.len() > 1 {
+        bail!("--name can only be used when linking a single path ({} paths given)", source_paths.len());
+    }
 
-    let source_path = PathBuf::from(&source);
-    let source_path = if source_path.is_absolute() {
-        source_path
-    } else {
-        std::env::current_dir()?.join(&source_path)
-    };
+    let requests = source_paths.into_iter()
+        .map(|source_path| LinkRequest {
+            source_path,
+            vault_path: name.clone(),
+            platform: platform.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    link_requests(&mut vault, &requests, yes)
+}
+
+/// Check, preview and link source paths, saving the manifest once.
+/// Nothing is linked if any path cannot be.
+pub fn link_requests(vault: &mut Vault, requests: &[LinkRequest], yes: bool) -> Result<()> {
+    let plans = plan_links(vault, requests)?;
 
-    // Infer or use provided vault path
-    let vault_relative_path = if let Some(n) = name {
-        n
+    let confirmed = if let [plan] = plans.as_slice() {
+        confirm_single(plan, yes)?
     } else {
-        infer_vault_path(&source_path)?
+        confirm_batch(&plans, yes)?
     };
+    if !confirmed {
+        println!("Cancelled.");
+        return Ok(());
+    }
 
-    // Check if already managed
-    if vault.manifest.get_file(&vault_relative_path).is_some() {
-        bail!(
-            "File already managed\nThe file {} is already in the vault as {}\n\nTo update it, use:\n  gfv backup",
-            source_path.display(),
-            vault_relative_path
+    // Create manifest entries, saved once for the whole batch
+    for plan in &plans {
+        let entry = FileEntry {
+            source_path: plan.source_path.display().to_string(),
+            file_type: plan.file_type.to_string(),
+            platform: plan.platform.clone(),
+            added_at: Utc::now(),
+            last_sync: None,  // No sync yet, just linking
+            sensitive: plan.sensitive,
+        };
+        vault.manifest.add_file(plan.vault_path.clone(), entry);
+    }
+    vault.save_manifest()
+        .context("Failed to save manifest")?;
+
+    println!("{} Updated manifest", "✓".green().bold());
+
+    if let [plan] = plans.as_slice() {
+        println!("\n{} is now managed by gfv.",
+            if plan.file_type == "directory" { "Directory" } else { "File" }
         );
+    } else {
+        let next = if plans.iter().all(|plan| plan.exists_in_vault) {
+            "Use 'gfv restore' to copy them into place."
+        } else {
+            "Use 'gfv backup' to upload them."
+        };
+        println!("\n{} paths are now managed by gfv. {}", plans.len(), next);
     }
 
-    // Get vault file path
-    let vault_file_path = vault.get_file_path(&vault_relative_path);
+    Ok(())
+}
+
+/// Check every source before anything is linked. Any problem (already managed,
+/// two sources with the same vault path, missing in both places) fails the whole batch.
+fn plan_links(vault: &Vault, requests: &[LinkRequest]) -> Result<Vec<LinkPlan>> {
+    let mut plans: Vec<LinkPlan> = Vec::new();
+    let mut problems = Vec::new();
+
+    for request in requests {
+        let source_path = &request.source_path;
+        // Infer or use provided vault path
+        let vault_path = match request.vault_path {
+            Some(ref n) => n.clone(),
+            None => infer_vault_path(source_path)?,
+        };
+
+        if let Some(existing) = vault.manifest.get_file(&vault_path) {
+            problems.push(format!("{} → {} is already managed (linked from {})",
+                source_path.display(), vault_path, existing.source_path));
+            continue;
+        }
+        if let Some(other) = plans.iter().find(|plan| plan.vault_path == vault_path) {
+            problems.push(format!("{} and {} would both be stored as {}",
+                other.source_path.display(), source_path.display(), vault_path));
+            continue;
+        }
+
+        // Check existence in both locations; must exist in at least one place
+        let vault_file_path = vault.get_file_path(&vault_path);
+        let exists_locally = source_path.exists();
+        let exists_in_vault = vault_file_path.exists();
+        if !exists_locally && !exists_in_vault {
+            problems.push(format!("{} not found locally or in the vault ({})",
+                source_path.display(), vault_file_path.display()));
+            continue;
+        }
 
-    // Check existence in both locations
-    let exists_locally = source_path.exists();
-    let exists_in_vault = vault_file_path.exists();
+        // Determine file type from whichever exists
+        let is_dir = if exists_locally { source_path.is_dir() } else { vault_file_path.is_dir() };
+
+        plans.push(LinkPlan {
+            source_path: source_path.clone(),
+            vault_path,
+            platform: request.platform.clone(),
+            file_type: if is_dir { "directory" } else { "file" },
+            exists_locally,
+            exists_in_vault,
+            // Check for sensitive files (only if exists locally)
+            sensitive: exists_locally && is_sensitive_file(source_path),
+        });
+    }
 
-    // Must exist in at least one place
-    if !exists_locally && !exists_in_vault {
-        bail!(
-            "File not found in either location:\n  Local: {}\n  Vault: {}",
-            source_path.display(),
-            vault_file_path.display()
-        );
+    if let ([problem], 1) = (problems.as_slice(), requests.len()) {
+        bail!("{}", problem);
+    }
+    if !problems.is_empty() {
+        let mut message = format!("Nothing was linked: {} of {} paths cannot be linked", problems.len(), requests.len());
+        for problem in &problems {
+            message.push_str(&format!("\n  - {}", problem));
+        }
+        message.push_str("\n\nUse --name for a single path, or leave the colliding paths out");
+        bail!(message);
     }
 
-    // Determine file type from whichever exists
-    let file_type = if exists_locally {
-        if source_path.is_dir() { "directory" } else { "file" }
-    } else {
-        if vault_file_path.is_dir() { "directory" } else { "file" }
-    };
+    Ok(plans)
+}
 
-    // Check for sensitive files (only if exists locally)
-    if exists_locally && is_sensitive_file(&source_path) {
+/// Details and sensitive-file prompt for a single path (the warning only, with `yes`)
+fn confirm_single(plan: &LinkPlan, yes: bool) -> Result<bool> {
+    if plan.sensitive {
         println!("{} Potentially sensitive file detected", "Warning:".yellow().bold());
-        println!("  {}", source_path.display());
+        println!("  {}", plan.source_path.display());
         println!("\nThis file may contain secrets or credentials.");
-        println!("Are you sure you want to add it to version control? (y/N)");
 
-        let mut input = String::new();
-        std::io::stdin().read_line(&mut input)?;
-
-        if !input.trim().eq_ignore_ascii_case("y") {
-            println!("Cancelled.");
-            return Ok(());
+        if !yes {
+            println!("Are you sure you want to add it to version control? (y/N)");
+            if !read_yes()? {
+                return Ok(false);
+            }
         }
     }
 
     println!("{} Linking {} {}",
         "==>".green().bold(),
-        source_path.display(),
-        if file_type == "directory" { "(directory)" } else { "" }
+        plan.source_path.display(),
+        if plan.file_type == "directory" { "(directory)" } else { "" }
     );
-    println!("  Vault path: {}", vault_relative_path);
-    println!("  Platform: {}", platform.as_deref().unwrap_or("all"));
+    println!("  Vault path: {}", plan.vault_path);
+    println!("  Platform: {}", plan.platform.as_deref().unwrap_or("all"));
 
-    if exists_locally && !exists_in_vault {
+    if plan.exists_locally && !plan.exists_in_vault {
         println!("{} File exists locally but not in vault", "→".blue());
         println!("   Use 'gfv backup' to upload it");
-    } else if !exists_locally && exists_in_vault {
+    } else if !plan.exists_locally && plan.exists_in_vault {
         println!("{} File exists in vault but not locally", "→".blue());
         println!("   Use 'gfv restore' to download it");
-    } else if exists_locally && exists_in_vault {
+    } else if plan.exists_locally && plan.exists_in_vault {
         println!("{} File exists in both locations", "→".blue());
     }
 
-    // Create manifest entry
-    let entry = FileEntry {
-        source_path: source_path.display().to_string(),
-        file_type: file_type.to_string(),
-        platform,
-        added_at: Utc::now(),
-        last_sync: None,  // No sync yet, just linking
-    };
+    Ok(true)
+}
 
-    // Update manifest
-    vault.manifest.add_file(vault_relative_path.clone(), entry);
-    vault.save_manifest()
-        .context("Failed to save manifest")?;
+/// Preview table for several paths, with one confirmation for all of them
+fn confirm_batch(plans: &[LinkPlan], yes: bool) -> Result<bool> {
+    println!("{} Linking {} paths", "==>".green().bold(), plans.len());
+
+    let sources: Vec<String> = plans.iter().map(|plan| plan.source_path.display().to_string()).collect();
+    let source_width = sources.iter().map(String::len).max().unwrap_or(0).max("SOURCE".len());
+    let vault_width = plans.iter().map(|plan| plan.vault_path.len()).max().unwrap_or(0).max("VAULT PATH".len());
+
+    println!("\n  {:<sw$}  {:<vw$}  TYPE  STATUS", "SOURCE", "VAULT PATH",
+        sw = source_width, vw = vault_width);
+    for (plan, source) in plans.iter().zip(&sources) {
+        let status = match (plan.exists_locally, plan.exists_in_vault) {
+            (true, false) => "local only".to_string(),
+            (false, _) => "vault only".to_string(),
+            (true, true) => "both".to_string(),
+        };
+        let status = if plan.sensitive { format!("{}, {}", status, "sensitive".yellow()) } else { status };
+        println!("  {:<sw$}  {:<vw$}  {:<4}  {}", source, plan.vault_path,
+            if plan.file_type == "directory" { "dir" } else { "file" }, status,
+            sw = source_width, vw = vault_width);
+    }
 
-    println!("{} Updated manifest", "✓".green().bold());
+    let sensitive = plans.iter().filter(|plan| plan.sensitive).count();
+    if sensitive > 0 {
+        println!("\n{} {} potentially sensitive {} may contain secrets or credentials",
+            "Warning:".yellow().bold(), sensitive, if sensitive == 1 { "file" } else { "files" });
+    }
 
-    println!("\n{} is now managed by gfv.",
-        if file_type == "directory" { "Directory" } else { "File" }
-    );
+    if yes {
+        return Ok(true);
+    }
+    println!("\nLink these {} paths? (y/N)", plans.len());
+    read_yes()
+}
 
-    Ok(())
+pub fn read_yes() -> Result<bool> {
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    Ok(input.trim().eq_ignore_ascii_case("y"))
+}
+
+/// Expand `~` and shell-independent globs (`*`, `?`, `[...]`, `{a,b}`, `**`) in
+/// source arguments. Plain paths are kept even if they do not exist locally.
+fn expand_sources(sources: &[String]) -> Result<Vec<PathBuf>> {
+    let mut paths: Vec<PathBuf> = Vec::new();
+
+    for source in sources {
+        let source_path = resolve_source_path(source)?;
+        if !is_glob(&source_path.to_string_lossy()) {
+            paths.push(source_path);
+            continue;
+        }
+
+        let matches = expand_glob(&source_path)
+            .with_context(|| format!("Invalid pattern: {}", source))?;
+        if matches.is_empty() {
+            bail!("No files match '{}'", source);
+        }
+        paths.extend(matches);
+    }
+
+    // The same file can be matched by several arguments
+    let mut seen = std::collections::HashSet::new();
+    paths.retain(|path| seen.insert(path.clone()));
+    Ok(paths)
+}
+
+fn is_glob(path: &str) -> bool {
+    path.contains(['*', '?', '[', '{'])
+}
+
+/// Paths matching an absolute glob, sorted. Walks from the longest literal prefix;
+/// `*` does not cross directories, `**` does.
+fn expand_glob(pattern: &Path) -> Result<Vec<PathBuf>> {
+    let mut base = PathBuf::new();
+    let mut rest = Vec::new();
+    for component in pattern.components() {
+        let part = component.as_os_str().to_string_lossy();
+        if rest.is_empty() && !is_glob(&part) {
+            base.push(component);
+        } else {
+            rest.push(part.into_owned());
+        }
+    }
+
+    let matcher = GlobBuilder::new(&pattern.to_string_lossy())
+        .literal_separator(true)
+        .build()?
+        .compile_matcher();
+    let max_depth = if rest.iter().any(|part| part.contains("**")) { usize::MAX } else { rest.len() };
+
+    let mut matches = Vec::new();
+    walk(&base, 1, max_depth, &mut |path| {
+        if matcher.is_match(path) {
+            matches.push(path.to_path_buf());
+        }
+    });
+    matches.sort();
+    Ok(matches)
+}
+
+/// Visit entries below `dir` down to `max_depth`, without following symlinked directories
+fn walk(dir: &Path, depth: usize, max_depth: usize, visit: &mut dyn FnMut(&Path)) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        visit(&path);
+        let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+        if is_dir && depth < max_depth {
+            walk(&path, depth + 1, max_depth, visit);
+        }
+    }
 }
 
 /// Infer vault path from source path based on common patterns
-fn infer_vault_path(source_path: &Path) -> Result<String> {
+pub fn infer_vault_path(source_path: &Path) -> Result<String> {
     let home = dirs::home_dir()
         .context("Failed to get home directory")?;
 
@@ -156,6 +340,11 @@ fn infer_vault_path(source_path: &Path) -> Result<String> {
         .and_then(|n| n.to_str())
         .context("Invalid file name")?;
 
+    // Well-known application settings have a canonical place
+    if let Some(vault_path) = catalog::vault_path_for(source_path) {
+        return Ok(vault_path.to_string());
+    }
+
     // If path is under ~/.config/, strip that prefix
     if let Ok(relative) = source_path.strip_prefix(home.join(".config")) {
         let parts: Vec<_> = relative.components().collect();
@@ -173,8 +362,6 @@ fn infer_vault_path(source_path: &Path) -> Result<String> {
         if let Some(base) = stripped.strip_suffix("rc") {
             // .zshrc -> zsh/zshrc
             return Ok(format!("{}/{}", base, stripped));
-        } else if stripped == "gitconfig" {
-            return Ok("git/gitconfig".to_string());
         } else if stripped.starts_with("ssh/") || file_name == ".ssh" {
             return Ok(format!("ssh/{}", stripped));
         } else {
@@ -183,11 +370,6 @@ fn infer_vault_path(source_path: &Path) -> Result<String> {
         }
     }
 
-    // Handle VSCode settings
-    if source_path.to_string_lossy().contains("Code/User/settings.json") {
-        return Ok("vscode/settings.json".to_string());
-    }
-
     // For other paths, strip home and use relative path
     if let Ok(relative) = source_path.strip_prefix(&home) {
         return Ok(relative.display().to_string());
@@ -198,7 +380,7 @@ fn infer_vault_path(source_path: &Path) -> Result<String> {
 }
 
 /// Check if file path contains sensitive patterns
-fn is_sensitive_file(path: &Path) -> bool {
+pub fn is_sensitive_file(path: &Path) -> bool {
     let path_str = path.to_string_lossy().to_lowercase();
 
     path_str.contains(".env") ||
@@ -2,11 +2,28 @@
 
 use anyhow::{bail, Context, Result};
 use colored::Colorize;
+use serde::Serialize;
 
-use crate::vault::Vault;
+use crate::output::{self, OutputFormat};
+use crate::vault::{Vault, manifest::FileEntry};
 use super::helpers::{get_vault_dir, get_active_vault_name};
 
-pub fn list(long: bool, vault: Option<String>) -> Result<()> {
+/// JSON output of `gfv list`
+#[derive(Serialize)]
+struct ListOutput<'a> {
+    vault: &'a str,
+    files: Vec<ListedFile<'a>>,
+}
+
+#[derive(Serialize)]
+struct ListedFile<'a> {
+    #[serde(rename = "vaultPath")]
+    vault_path: &'a str,
+    #[serde(flatten)]
+    entry: &'a FileEntry,
+}
+
+pub fn list(long: bool, vault: Option<String>, format: OutputFormat) -> Result<()> {
     // Get vault directory
     let vault_name = vault.unwrap_or_else(get_active_vault_name);
     let vault_dir = get_vault_dir(&vault_name)?;
@@ -20,6 +37,15 @@ pub fn list(long: bool, vault: Option<String>) -> Result<()> {
     let vault = Vault::load(&vault_dir)
         .context("Failed to load vault")?;
 
+    if format.is_json() {
+        let mut files: Vec<ListedFile> = vault.manifest.files.iter()
+            .map(|(vault_path, entry)| ListedFile { vault_path, entry })
+            .collect();
+        files.sort_by_key(|f| f.vault_path);
+
+        return output::print_json(&ListOutput { vault: &vault_name, files });
+    }
+
     // Check if empty
     if vault.manifest.files.is_empty() {
         println!("No files managed by gfv yet.");
@@ -60,10 +86,11 @@ pub fn list(long: bool, vault: Option<String>) -> Result<()> {
                 String::new()
             };
 
-            let type_icon = if entry.file_type == "directory" {
-                "📁"
-            } else {
-                "📄"
+            let type_icon = match (entry.file_type == "directory", output::use_emoji()) {
+                (true, true) => "📁",
+                (false, true) => "📄",
+                (true, false) => "d",
+                (false, false) => "f",
             };
 
             // Show vault path → source path mapping
@@ -0,0 +1,126 @@
+// Mv command implementation - changes the vault path of an entry
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use std::fs;
+use std::path::{Component, Path};
+
+use crate::vault::Vault;
+use crate::git_ops::GitRepo;
+use super::helpers::{get_vault_dir, get_active_vault_name, display_path, is_within, vault_support_files, remove_empty_parents};
+
+pub fn mv(
+    old: String,
+    new: String,
+    vault: Option<String>,
+) -> Result<()> {
+    // Get vault directory
+    let vault_name = vault.unwrap_or_else(get_active_vault_name);
+    let vault_dir = get_vault_dir(&vault_name)?;
+
+    // Check if vault is initialized
+    if !Vault::is_initialized(&vault_dir) {
+        bail!("Vault not initialized. Run 'gfv init' first.");
+    }
+
+    // Load vault
+    let mut vault = Vault::load(&vault_dir)
+        .context("Failed to load vault")?;
+
+    let old = old.trim_end_matches('/').to_string();
+    if vault.manifest.get_file(&old).is_none() {
+        bail!("File '{}' is not managed by gfv.\n\nList managed files with: gfv list", old);
+    }
+    let new = check_new_path(&vault, &old, &new)?;
+
+    // The commit below must contain only the move
+    let from = vault.get_file_path(&old);
+    let to = vault.get_file_path(&new);
+    let moved = from.exists();
+    let git_repo = GitRepo::open(&vault.repo_path)
+        .context("Failed to open git repository")?;
+    if moved && git_repo.has_changes()? {
+        bail!("Vault has uncommitted changes. Run 'gfv backup' first.");
+    }
+
+    println!("{} Moving {} to {}...", "==>".green().bold(), old, new);
+
+    // Move the file or tree inside the repo; git sees it as a rename once both sides are staged
+    if moved {
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        fs::rename(&from, &to)
+            .with_context(|| format!("Failed to move {} to {}", from.display(), to.display()))?;
+        remove_empty_parents(&from, &vault.repo_path);
+        println!("  {} Moved in vault", "✓".green());
+    } else if to.exists() {
+        println!("  {} Already moved in vault; only the entry is renamed", "→".blue());
+    } else {
+        println!("  {} Not in the vault yet; only the entry is renamed", "→".blue());
+    }
+
+    // Re-key the entry, keeping its metadata
+    let entry = vault.manifest.remove_file(&old)
+        .expect("File should exist"); // We just checked above
+    let source_path = entry.source_path.clone();
+    vault.manifest.add_file(new.clone(), entry);
+    vault.save_manifest()
+        .context("Failed to save manifest")?;
+    println!("  {} Updated manifest", "✓".green());
+
+    // Commit only the two sides of the move
+    if moved {
+        git_repo.stage_paths(&[&old, &new])
+            .context("Failed to stage changes")?;
+        git_repo.commit(&format!("Move {} to {}", old, new))
+            .context("Failed to commit changes")?;
+        println!("  {} Committed changes", "✓".green());
+    }
+
+    println!("\nSource location unchanged: {}", display_path(Path::new(&source_path)));
+    println!("Change it with: gfv relink {} <new-source>", new);
+
+    Ok(())
+}
+
+/// Normalize the new vault path and make sure nothing is in its way
+fn check_new_path(vault: &Vault, old: &str, new: &str) -> Result<String> {
+    let new = new.trim_end_matches('/');
+    let path = Path::new(new);
+    if new.is_empty() || !path.components().all(|c| matches!(c, Component::Normal(_))) {
+        bail!("Invalid vault path '{}': use a relative path without '.' or '..'", new);
+    }
+    let new = path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+
+    if new == old {
+        bail!("{} is already the vault path", old);
+    }
+    if new == ".git" || new.starts_with(".git/") || vault_support_files(vault).contains(&new) {
+        bail!("{} is reserved by the vault", new);
+    }
+    if is_within(&new, old) {
+        bail!("Cannot move {} into itself", old);
+    }
+    if is_within(old, &new) {
+        bail!("Cannot move {} onto {}, which contains it", old, new);
+    }
+    if let Some(existing) = vault.manifest.get_file(&new) {
+        bail!("{} is already managed (linked from {})", new, existing.source_path);
+    }
+    if let Some(other) = vault.manifest.files.keys()
+        .find(|entry| entry.as_str() != old && (is_within(&new, entry) || is_within(entry, &new)))
+    {
+        bail!("{} overlaps the managed entry {}", new, other);
+    }
+    // Fine if the copy was already moved, e.g. by a pulled `gfv mv` from another machine
+    if vault.get_file_path(&new).exists() && vault.get_file_path(old).exists() {
+        bail!("{} already exists in the vault repo\n\nMap it to a source path with: gfv adopt", new);
+    }
+
+    Ok(new)
+}
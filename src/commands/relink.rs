@@ -0,0 +1,74 @@
+// Relink command implementation - changes where an entry lives on disk
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use std::path::Path;
+
+use crate::vault::Vault;
+use super::helpers::{get_vault_dir, get_active_vault_name, resolve_source_path, display_path};
+use super::link::is_sensitive_file;
+
+pub fn relink(
+    file: String,
+    source: String,
+    vault: Option<String>,
+) -> Result<()> {
+    // Get vault directory
+    let vault_name = vault.unwrap_or_else(get_active_vault_name);
+    let vault_dir = get_vault_dir(&vault_name)?;
+
+    // Check if vault is initialized
+    if !Vault::is_initialized(&vault_dir) {
+        bail!("Vault not initialized. Run 'gfv init' first.");
+    }
+
+    // Load vault
+    let mut vault = Vault::load(&vault_dir)
+        .context("Failed to load vault")?;
+
+    let file = file.trim_end_matches('/').to_string();
+    let Some(entry) = vault.manifest.get_file(&file) else {
+        bail!("File '{}' is not managed by gfv.\n\nList managed files with: gfv list", file);
+    };
+    let is_dir = entry.file_type == "directory";
+    let old_source = entry.source_path.clone();
+
+    let source_path = resolve_source_path(&source)?;
+    if Path::new(&old_source) == source_path {
+        bail!("{} is already linked from {}", file, source_path.display());
+    }
+    if let Some((other, _)) = vault.manifest.files.iter()
+        .find(|(_, other)| Path::new(&other.source_path) == source_path)
+    {
+        bail!("{} is already linked as {}", source_path.display(), other);
+    }
+    let exists_locally = source_path.exists();
+    if exists_locally && source_path.is_dir() != is_dir {
+        bail!("{} is a {}, but {} is a {}", source_path.display(),
+            if is_dir { "file" } else { "directory" }, file,
+            if is_dir { "directory" } else { "file" });
+    }
+
+    println!("{} Relinking {} to {}...", "==>".green().bold(), file, display_path(&source_path));
+
+    // Only the manifest changes; the vault copy and both locations on disk stay as they are
+    let entry = vault.manifest.files.get_mut(&file)
+        .expect("File should exist"); // We just checked above
+    entry.source_path = source_path.to_string_lossy().into_owned();
+    if exists_locally && is_sensitive_file(&source_path) && !entry.sensitive {
+        entry.sensitive = true;
+        println!("  {} Marked as sensitive", "⚠".yellow());
+    }
+    vault.save_manifest()
+        .context("Failed to save manifest")?;
+    println!("  {} Updated manifest", "✓".green());
+
+    println!("\nOld location left as is: {}", display_path(Path::new(&old_source)));
+    if exists_locally {
+        println!("Compare it with the vault copy with: gfv status");
+    } else {
+        println!("Put the vault copy there with: gfv restore");
+    }
+
+    Ok(())
+}
@@ -2,11 +2,13 @@
 
 use anyhow::{bail, Context, Result};
 use colored::Colorize;
+use std::collections::BTreeMap;
 use std::fs;
+use std::path::Path;
 
-use crate::vault::Vault;
-use crate::git_ops::GitRepo;
-use super::helpers::{get_vault_dir, get_active_vault_name};
+use crate::vault::{Vault, manifest::FileEntry};
+use crate::git_ops::{AuthOptions, EntryContent, GitRepo};
+use super::helpers::{get_vault_dir, get_active_vault_name, is_within};
 
 pub fn restore(
     _rebase: bool,
@@ -28,22 +30,27 @@ pub fn restore(
     let vault = Vault::load(&vault_dir)
         .context("Failed to load vault")?;
 
+    if dry_run {
+        return preview_restore(&vault);
+    }
+
     println!("{} Restoring from vault...", "==>".green().bold());
 
     // Step 1: Pull from remote if configured
-    if let Some(ref remote_config) = vault.manifest.remote {
+    if let Some((remote_name, remote_config)) = vault.manifest.primary_remote() {
         println!("  {} Pulling from remote...", "==>".green());
 
         let git_repo = GitRepo::open(&vault.repo_path)
-            .context("Failed to open git repository")?;
+            .context("Failed to open git repository")?
+            .with_auth(AuthOptions::from_remote(remote_config));
 
         // Get the actual current branch (it might differ from manifest)
         let current_branch = git_repo.current_branch()
             .unwrap_or_else(|_| remote_config.branch.clone());
 
-        match git_repo.pull("origin", &current_branch, true) {
+        match git_repo.pull(remote_name, &current_branch, true) {
             Ok(_) => {
-                println!("    {} Pulled from origin/{}", "✓".green(), current_branch);
+                println!("    {} Pulled from {}/{}", "✓".green(), remote_name, current_branch);
             }
             Err(e) => {
                 eprintln!("{} Failed to pull from remote: {}", "✗".red().bold(), e);
@@ -56,7 +63,7 @@ pub fn restore(
     // If no files to restore, exit early after pulling
     if vault.manifest.files.is_empty() {
         println!("\n{} No linked files to restore.", "✓".green().bold());
-        if vault.manifest.remote.is_some() {
+        if vault.manifest.has_remote() {
             println!("Git repository has been updated.");
         } else {
             println!("Vault is empty.");
@@ -64,8 +71,14 @@ pub fn restore(
         return Ok(());
     }
 
+    restore_files(&vault, force)
+}
+
+/// Copy every entry from the vault to its source location, asking before
+/// overwriting local changes unless `force` is set
+pub fn restore_files(vault: &Vault, force: bool) -> Result<()> {
     // Step 2: Check for uncommitted source changes (simplified for MVP)
-    if !force && !dry_run {
+    if !force {
         let mut has_local_changes = false;
         let mut changed_files = Vec::new();
 
@@ -125,37 +138,14 @@ pub fn restore(
         }
 
         // Check platform restriction
-        if let Some(ref platform) = entry.platform {
-            let current_os = std::env::consts::OS;
-            
-            // Known platform identifiers
-            const KNOWN_PLATFORMS: &[&str] = &["macos", "linux", "windows"];
-            
-            // Only skip if:
-            // 1. Platform is a known OS name AND
-            // 2. It doesn't match the current OS
-            // Non-standard platform values (e.g., "any", "cross-platform") are never skipped
-            let should_skip = KNOWN_PLATFORMS.contains(&platform.as_str()) 
-                && platform.as_str() != current_os;
-
-            if should_skip {
-                println!("  {} Skipping {} (platform: {} != {})",
-                    "⚠".yellow(),
-                    vault_relative_path,
-                    platform,
-                    current_os
-                );
-                files_skipped += 1;
-                continue;
-            }
-        }
-
-        if dry_run {
-            println!("  Would restore: {} -> {}",
+        if let Some(platform) = skipped_platform(entry) {
+            println!("  {} Skipping {} (platform: {} != {})",
+                "⚠".yellow(),
                 vault_relative_path,
-                source_path.display()
+                platform,
+                std::env::consts::OS
             );
-            files_restored += 1;
+            files_skipped += 1;
             continue;
         }
 
@@ -181,25 +171,226 @@ pub fn restore(
 
     // Step 4: Report results
     println!();
-    if dry_run {
-        println!("{} Would restore {} files (skipped {})",
-            "✓".green().bold(),
-            files_restored,
-            files_skipped
-        );
-        println!("Run without --dry-run to apply changes.");
-    } else {
-        println!("{} Restored {} files (skipped {})",
-            "✓".green().bold(),
-            files_restored,
-            files_skipped
-        );
-        println!("Your files are up to date!");
+    println!("{} Restored {} files (skipped {})",
+        "✓".green().bold(),
+        files_restored,
+        files_skipped
+    );
+    println!("Your files are up to date!");
+
+    Ok(())
+}
+
+/// What restoring would do to a single file
+#[derive(Clone, Copy, PartialEq)]
+enum Action {
+    Create,
+    Overwrite,
+    Delete,
+    Unchanged,
+}
+
+impl Action {
+    fn label(self) -> colored::ColoredString {
+        match self {
+            Action::Create => "create".green(),
+            Action::Overwrite => "overwrite".yellow(),
+            Action::Delete => "delete".red(),
+            Action::Unchanged => "unchanged".dimmed(),
+        }
+    }
+}
+
+/// Show what `restore` would do without pulling or writing anything.
+/// The remote is only fetched; entries changed upstream are compared using
+/// the fetched tree, everything else using the local vault copy.
+fn preview_restore(vault: &Vault) -> Result<()> {
+    println!("{} Previewing restore (dry run)...", "==>".green().bold());
+
+    // Step 1: Fetch (never merge) to learn what a pull would bring in
+    let mut upstream: Option<(String, String, Vec<String>)> = None;
+
+    if let Some((remote_name, remote_config)) = vault.manifest.primary_remote() {
+        let git_repo = GitRepo::open(&vault.repo_path)
+            .context("Failed to open git repository")?
+            .with_auth(AuthOptions::from_remote(remote_config));
+
+        let current_branch = git_repo.current_branch()
+            .unwrap_or_else(|_| remote_config.branch.clone());
+
+        match git_repo.fetch(remote_name, &current_branch) {
+            Ok(_) => println!("  {} Fetched from {}/{}", "✓".green(), remote_name, current_branch),
+            Err(e) => {
+                eprintln!("  {} Failed to fetch from remote: {:#}", "⚠".yellow(), e);
+                eprintln!("  {} Using last known remote state", "→".yellow());
+            }
+        }
+
+        match git_repo.ahead_behind(remote_name, &current_branch)? {
+            Some((_, behind)) if behind > 0 => {
+                println!("  {} Pull would bring in {} {}",
+                    "↓".cyan(), behind, if behind == 1 { "commit" } else { "commits" });
+                let (_, paths) = git_repo.divergent_paths(remote_name, &current_branch)?;
+                upstream = Some((remote_name.to_string(), current_branch, paths));
+            }
+            Some(_) => println!("  {} Vault is up to date with remote", "✓".green()),
+            None => println!("  {} Remote branch does not exist yet", "→".blue()),
+        }
+    }
+
+    if vault.manifest.files.is_empty() {
+        println!("\n{} No linked files to restore.", "✓".green().bold());
+        return Ok(());
+    }
+
+    let git_repo = GitRepo::open(&vault.repo_path)
+        .context("Failed to open git repository")?;
+
+    let mut sorted_paths: Vec<_> = vault.manifest.files.keys().collect();
+    sorted_paths.sort();
+
+    let mut entries_restored = 0;
+    let mut files_skipped = 0;
+    let mut counts = [0usize; 3];
+
+    println!();
+    for vault_relative_path in sorted_paths {
+        let entry = &vault.manifest.files[vault_relative_path];
+        let source_path = std::path::PathBuf::from(&entry.source_path);
+
+        if let Some(platform) = skipped_platform(entry) {
+            println!("  {} Skipping {} (platform: {} != {})",
+                "⚠".yellow(), vault_relative_path, platform, std::env::consts::OS);
+            files_skipped += 1;
+            continue;
+        }
+
+        // Pick the version a pull would leave in the vault
+        let changed_upstream = upstream.as_ref().and_then(|(remote, branch, paths)| {
+            paths.iter()
+                .any(|p| is_within(p, vault_relative_path))
+                .then_some((remote, branch))
+        });
+        let content = match changed_upstream {
+            Some((remote, branch)) => git_repo.read_remote_entry(remote, branch, vault_relative_path)?,
+            None => read_working_entry(&vault.get_file_path(vault_relative_path))?,
+        };
+
+        let Some(content) = content else {
+            let reason = if changed_upstream.is_some() { "removed upstream" } else { "not in vault" };
+            println!("  {} Skipping {} ({})", "⚠".yellow(), vault_relative_path, reason);
+            files_skipped += 1;
+            continue;
+        };
+
+        let marker = if changed_upstream.is_some() { " (changed upstream)".cyan().to_string() } else { String::new() };
+        println!("  {} -> {}{}", vault_relative_path, source_path.display(), marker);
+
+        for (action, path) in plan_entry(&content, &source_path) {
+            match action {
+                Action::Create => counts[0] += 1,
+                Action::Overwrite => counts[1] += 1,
+                Action::Delete => counts[2] += 1,
+                Action::Unchanged => continue,
+            }
+            println!("      {:<9} {}", action.label(), path);
+        }
+        entries_restored += 1;
     }
 
+    println!();
+    println!("{} Would restore {} entries: {} create, {} overwrite, {} delete (skipped {})",
+        "✓".green().bold(),
+        entries_restored,
+        counts[0],
+        counts[1],
+        counts[2],
+        files_skipped
+    );
+    println!("Nothing was changed. Run without --dry-run to apply changes.");
+
     Ok(())
 }
 
+/// Compute per-file actions for restoring `content` to `source_path`
+fn plan_entry(content: &EntryContent, source_path: &Path) -> Vec<(Action, String)> {
+    let compare = |data: &[u8], target: &Path| {
+        match fs::read(target) {
+            Ok(existing) if existing == data => Action::Unchanged,
+            Ok(_) => Action::Overwrite,
+            Err(_) => Action::Create,
+        }
+    };
+
+    match content {
+        EntryContent::File(data) => {
+            vec![(compare(data, source_path), source_path.display().to_string())]
+        }
+        EntryContent::Directory(files) => {
+            let mut actions: Vec<(Action, String)> = files.iter()
+                .map(|(relative, data)| (compare(data, &source_path.join(relative)), relative.clone()))
+                .collect();
+
+            // Directory restore removes files that are not in the vault
+            if source_path.is_dir() {
+                for item in walkdir::WalkDir::new(source_path).into_iter().flatten() {
+                    if !item.file_type().is_file() {
+                        continue;
+                    }
+                    if let Ok(relative) = item.path().strip_prefix(source_path) {
+                        let relative = relative.to_string_lossy().replace('\\', "/");
+                        if !files.contains_key(&relative) {
+                            actions.push((Action::Delete, relative));
+                        }
+                    }
+                }
+            }
+
+            actions.sort_by(|a, b| a.1.cmp(&b.1));
+            actions
+        }
+    }
+}
+
+/// Read a vault entry from the repo working tree
+fn read_working_entry(path: &Path) -> Result<Option<EntryContent>> {
+    if path.is_file() {
+        return Ok(Some(EntryContent::File(fs::read(path)?)));
+    }
+    if !path.is_dir() {
+        return Ok(None);
+    }
+
+    let mut files = BTreeMap::new();
+    for item in walkdir::WalkDir::new(path).into_iter().flatten() {
+        if item.file_type().is_file() {
+            if let Ok(relative) = item.path().strip_prefix(path) {
+                let relative = relative.to_string_lossy().replace('\\', "/");
+                files.insert(relative, fs::read(item.path())?);
+            }
+        }
+    }
+
+    Ok(Some(EntryContent::Directory(files)))
+}
+
+/// Platform an entry is restricted to, if it does not match this OS
+fn skipped_platform(entry: &FileEntry) -> Option<&str> {
+    let platform = entry.platform.as_deref()?;
+
+    // Known platform identifiers
+    const KNOWN_PLATFORMS: &[&str] = &["macos", "linux", "windows"];
+
+    // Only skip if:
+    // 1. Platform is a known OS name AND
+    // 2. It doesn't match the current OS
+    // Non-standard platform values (e.g., "any", "cross-platform") are never skipped
+    let should_skip = KNOWN_PLATFORMS.contains(&platform)
+        && platform != std::env::consts::OS;
+
+    should_skip.then_some(platform)
+}
+
 /// Synchronize directory from source to destination
 /// - Copies all files/dirs from src to dst
 /// - Removes files/dirs in dst that don't exist in src
@@ -0,0 +1,219 @@
+// Scan command implementation
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::io::IsTerminal;
+
+use crate::catalog::{self, Found};
+use crate::output::{self, OutputFormat};
+use crate::vault::Vault;
+use super::helpers::{get_vault_dir, get_active_vault_name, display_path};
+use super::link::{self, LinkRequest};
+
+/// Whether a found file is already in the vault
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum ScanStatus {
+    /// Not managed yet
+    New,
+    /// Linked from this source path
+    Managed,
+    /// The canonical vault path is used by another entry
+    PathTaken,
+}
+
+/// JSON output of `gfv scan`
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ScanOutput<'a> {
+    vault: &'a str,
+    items: Vec<ScanItem<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ScanItem<'a> {
+    app: &'a str,
+    name: &'a str,
+    source_path: String,
+    vault_path: &'a str,
+    status: ScanStatus,
+}
+
+pub fn scan(
+    link: Vec<String>,
+    all: bool,
+    yes: bool,
+    vault: Option<String>,
+    format: OutputFormat,
+) -> Result<()> {
+    // Get vault directory
+    let vault_name = vault.unwrap_or_else(get_active_vault_name);
+    let vault_dir = get_vault_dir(&vault_name)?;
+
+    // Check if vault is initialized
+    if !Vault::is_initialized(&vault_dir) {
+        bail!("Vault not initialized. Run 'gfv init' first.");
+    }
+
+    // Load vault
+    let mut vault = Vault::load(&vault_dir)
+        .context("Failed to load vault")?;
+
+    let found: Vec<(Found, ScanStatus)> = catalog::scan()?
+        .into_iter()
+        .map(|item| {
+            let status = status_of(&vault, &item);
+            (item, status)
+        })
+        .filter(|(_, status)| all || *status != ScanStatus::Managed)
+        .collect();
+
+    if format.is_json() {
+        let items: Vec<ScanItem> = found.iter()
+            .map(|(item, status)| ScanItem {
+                app: &item.app.id,
+                name: &item.app.name,
+                source_path: item.source_path.display().to_string(),
+                vault_path: &item.file.vault_path,
+                status: *status,
+            })
+            .collect();
+        return output::print_json(&ScanOutput { vault: &vault_name, items });
+    }
+
+    // Only new files can be linked; they are numbered for selection
+    let new: Vec<&Found> = found.iter()
+        .filter(|(_, status)| *status == ScanStatus::New)
+        .map(|(item, _)| item)
+        .collect();
+
+    if found.is_empty() {
+        println!("{} No unmanaged settings files found ({} apps in the catalog)",
+            "✓".green(), catalog::apps().len());
+        return Ok(());
+    }
+
+    print_table(&found);
+
+    if new.is_empty() {
+        println!("\n{} Everything found is already managed", "✓".green());
+        return Ok(());
+    }
+
+    // Pick what to link: --link, an interactive answer, or nothing
+    let (selected, confirmed) = if !link.is_empty() {
+        (select(&new, &link)?, yes)
+    } else if std::io::stdin().is_terminal() {
+        println!("\nLink which? Numbers or ranges (1,3-5), app names, 'all', or Enter to skip:");
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+        let tokens: Vec<String> = input.split([',', ' ', '\t', '\n'])
+            .filter(|token| !token.trim().is_empty())
+            .map(|token| token.trim().to_string())
+            .collect();
+        if tokens.is_empty() {
+            return Ok(());
+        }
+        // Choosing from the table is the confirmation
+        (select(&new, &tokens)?, true)
+    } else {
+        println!("\nLink them with: gfv scan --link <app>... (or --link all)");
+        return Ok(());
+    };
+
+    let requests: Vec<LinkRequest> = selected.iter()
+        .map(|item| LinkRequest {
+            source_path: item.source_path.clone(),
+            vault_path: Some(item.file.vault_path.clone()),
+            platform: item.app.platform.clone(),
+        })
+        .collect();
+
+    println!();
+    link::link_requests(&mut vault, &requests, confirmed)
+}
+
+fn status_of(vault: &Vault, item: &Found) -> ScanStatus {
+    let source = item.source_path.display().to_string();
+    if vault.manifest.files.values().any(|entry| entry.source_path == source) {
+        return ScanStatus::Managed;
+    }
+    if vault.manifest.get_file(&item.file.vault_path).is_some() {
+        return ScanStatus::PathTaken;
+    }
+    ScanStatus::New
+}
+
+fn print_table(found: &[(Found, ScanStatus)]) {
+    let new_count = found.iter().filter(|(_, status)| *status == ScanStatus::New).count();
+    println!("{} Found {} settings {} not managed yet", "==>".green().bold(),
+        new_count, if new_count == 1 { "file" } else { "files" });
+
+    let sources: Vec<String> = found.iter().map(|(item, _)| display_path(&item.source_path)).collect();
+    let app_width = found.iter().map(|(item, _)| item.app.name.len()).max().unwrap_or(0).max("APP".len());
+    let source_width = sources.iter().map(String::len).max().unwrap_or(0).max("SOURCE".len());
+
+    println!("\n  {:>3}  {:<aw$}  {:<sw$}  VAULT PATH", "#", "APP", "SOURCE",
+        aw = app_width, sw = source_width);
+    let mut number = 0;
+    for ((item, status), source) in found.iter().zip(&sources) {
+        let (index, note) = match status {
+            ScanStatus::New => {
+                number += 1;
+                (number.to_string(), String::new())
+            }
+            ScanStatus::Managed => ("-".to_string(), format!("  {}", "(managed)".dimmed())),
+            ScanStatus::PathTaken => ("-".to_string(), format!("  {}", "(vault path in use)".yellow())),
+        };
+        println!("  {:>3}  {:<aw$}  {:<sw$}  {}{}", index, item.app.name, source, item.file.vault_path, note,
+            aw = app_width, sw = source_width);
+    }
+}
+
+/// Resolve selection tokens (numbers, ranges, app ids, `all`) against the new files
+fn select<'a>(new: &[&'a Found], tokens: &[String]) -> Result<Vec<&'a Found>> {
+    let mut chosen = BTreeSet::new();
+
+    for token in tokens {
+        let token = token.trim();
+        if token.eq_ignore_ascii_case("all") {
+            chosen.extend(0..new.len());
+            continue;
+        }
+
+        let range = match token.split_once('-') {
+            Some((start, end)) => start.parse::<usize>().ok().zip(end.parse::<usize>().ok()),
+            None => token.parse::<usize>().ok().map(|n| (n, n)),
+        };
+        if let Some((start, end)) = range {
+            if start == 0 || end > new.len() || start > end {
+                bail!("Invalid selection '{}' (choose between 1 and {})", token, new.len());
+            }
+            chosen.extend(start - 1..end);
+            continue;
+        }
+
+        // An app id from the catalog
+        if !catalog::apps().iter().any(|app| app.id == token) {
+            bail!("Unknown app '{}'. Known apps: {}", token,
+                catalog::apps().iter().map(|app| app.id.as_str()).collect::<Vec<_>>().join(", "));
+        }
+        let matches: Vec<usize> = new.iter()
+            .enumerate()
+            .filter(|(_, item)| item.app.id == token)
+            .map(|(i, _)| i)
+            .collect();
+        if matches.is_empty() {
+            println!("{} Nothing new to link for {}", "→".blue(), token);
+        }
+        chosen.extend(matches);
+    }
+
+    if chosen.is_empty() {
+        bail!("Nothing selected");
+    }
+    Ok(chosen.into_iter().map(|i| new[i]).collect())
+}
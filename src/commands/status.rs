@@ -2,13 +2,57 @@
 
 use anyhow::{bail, Context, Result};
 use colored::Colorize;
+use serde::Serialize;
 use std::fs;
 
 use crate::vault::Vault;
-use crate::git_ops::GitRepo;
-use super::helpers::{get_vault_dir, get_active_vault_name};
+use crate::git_ops::{AuthOptions, GitRepo};
+use crate::output::{self, OutputFormat};
+use super::helpers::{get_vault_dir, get_active_vault_name, is_within};
 
-pub fn status(vault: Option<String>) -> Result<()> {
+/// Status of a vault, shared by the text and JSON output
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StatusReport {
+    vault: String,
+    path: String,
+    primary_remote: Option<RemoteInfo>,
+    mirrors: Vec<RemoteInfo>,
+    managed_files: usize,
+    uncommitted_changes: bool,
+    modified: Vec<String>,
+    missing_source: Vec<String>,
+    up_to_date: Vec<String>,
+    /// Unpushed commits against the last known remote state (without --remote)
+    unpushed_commits: Option<bool>,
+    /// Only present with --remote
+    remote_status: Option<RemoteStatus>,
+}
+
+#[derive(Serialize)]
+struct RemoteInfo {
+    name: String,
+    url: String,
+    branch: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RemoteStatus {
+    remote: String,
+    branch: String,
+    fetched: bool,
+    /// False when the remote branch does not exist yet
+    branch_exists: bool,
+    ahead: usize,
+    behind: usize,
+    /// Entries changed upstream and not yet restored
+    incoming: Vec<String>,
+    /// Entries changed both locally and upstream
+    conflicts: Vec<String>,
+}
+
+pub fn status(vault: Option<String>, remote: bool, format: OutputFormat) -> Result<()> {
     // Get vault path
     let vault_name = vault.unwrap_or_else(get_active_vault_name);
     let vault_dir = get_vault_dir(&vault_name)?;
@@ -23,39 +67,49 @@ pub fn status(vault: Option<String>) -> Result<()> {
     let vault = Vault::load(&vault_dir)
         .context("Failed to load vault")?;
 
-    // Display vault info
-    println!("{}", "Vault Status".bold());
-    println!("  Path: {}", vault_dir.display());
+    let report = collect_status(&vault_name, &vault, remote, format)?;
 
-    if let Some(ref remote_config) = vault.manifest.remote {
-        println!("  Remote: {} ({})", remote_config.url, remote_config.branch);
+    if format.is_json() {
+        output::print_json(&report)
     } else {
-        println!("  Remote: {} (local-only mode)", "None".yellow());
+        print_status(&report, remote);
+        Ok(())
     }
+}
 
-    println!("  Managed files: {}", vault.manifest.files.len());
+fn collect_status(vault_name: &str, vault: &Vault, remote: bool, format: OutputFormat) -> Result<StatusReport> {
+    let primary = vault.manifest.primary_remote();
+
+    let primary_remote = primary.map(|(name, config)| RemoteInfo {
+        name: name.to_string(),
+        url: config.url.clone(),
+        branch: config.branch.clone(),
+    });
+    let mirrors = vault.manifest.remotes.iter()
+        .filter(|(name, _)| Some(name.as_str()) != primary.map(|(p, _)| p))
+        .map(|(name, config)| RemoteInfo {
+            name: name.clone(),
+            url: config.url.clone(),
+            branch: config.branch.clone(),
+        })
+        .collect();
 
     // Check Git status
     let git_repo = GitRepo::open(&vault.repo_path)
         .context("Failed to open git repository")?;
 
-    let has_changes = git_repo.has_changes()?;
-
-    if has_changes {
-        println!("\n{} Uncommitted changes in vault", "●".yellow().bold());
-        println!("  Run 'gfv backup' to commit changes");
-    } else {
-        println!("\n{} Vault is clean", "✓".green().bold());
-    }
+    let uncommitted_changes = git_repo.has_changes()?;
 
     // Check for differences between source and vault
-    println!("\n{}", "File Status:".bold());
-
     let mut modified = Vec::new();
     let mut missing_source = Vec::new();
     let mut up_to_date = Vec::new();
 
-    for (vault_relative_path, entry) in &vault.manifest.files {
+    let mut sorted_paths: Vec<_> = vault.manifest.files.keys().collect();
+    sorted_paths.sort();
+
+    for vault_relative_path in sorted_paths {
+        let entry = &vault.manifest.files[vault_relative_path];
         let source_path = std::path::PathBuf::from(&entry.source_path);
         let vault_file_path = vault.get_file_path(vault_relative_path);
 
@@ -82,24 +136,189 @@ pub fn status(vault: Option<String>) -> Result<()> {
         }
     }
 
-    if !modified.is_empty() {
+    let mut report = StatusReport {
+        vault: vault_name.to_string(),
+        path: vault.vault_dir.display().to_string(),
+        primary_remote,
+        mirrors,
+        managed_files: vault.manifest.files.len(),
+        uncommitted_changes,
+        modified,
+        missing_source,
+        up_to_date,
+        unpushed_commits: None,
+        remote_status: None,
+    };
+
+    let Some((remote_name, remote_config)) = primary else {
+        return Ok(report);
+    };
+
+    // Get the actual current branch (it might differ from manifest)
+    let current_branch = git_repo.current_branch()
+        .unwrap_or_else(|_| remote_config.branch.clone());
+
+    if !remote {
+        // Without fetching we can only compare against the last known remote state
+        report.unpushed_commits = git_repo.has_unpushed_commits(remote_name, &current_branch).ok();
+        return Ok(report);
+    }
+
+    // Fetch to see what the remote has
+    if !format.is_json() {
+        println!("{} Fetching from {}...", "==>".green().bold(), remote_name);
+    }
+    let git_repo = git_repo.with_auth(AuthOptions::from_remote(remote_config));
+    let fetched = match git_repo.fetch(remote_name, &current_branch) {
+        Ok(_) => true,
+        Err(e) => {
+            eprintln!("{} Failed to fetch from remote: {:#}", "✗".red().bold(), e);
+            eprintln!("Showing last known remote state.");
+            false
+        }
+    };
+
+    let mut remote_status = RemoteStatus {
+        remote: remote_name.to_string(),
+        branch: current_branch.clone(),
+        fetched,
+        branch_exists: false,
+        ahead: 0,
+        behind: 0,
+        incoming: Vec::new(),
+        conflicts: Vec::new(),
+    };
+
+    if let Some((ahead, behind)) = git_repo.ahead_behind(remote_name, &current_branch)? {
+        remote_status.branch_exists = true;
+        remote_status.ahead = ahead;
+        remote_status.behind = behind;
+    }
+
+    if remote_status.behind > 0 {
+        // Map changed repo paths onto manifest entries
+        let (local_paths, upstream_paths) = git_repo.divergent_paths(remote_name, &current_branch)?;
+        let touches = |paths: &[String], vault_path: &str| {
+            paths.iter().any(|p| is_within(p, vault_path))
+        };
+
+        let mut entries: Vec<&String> = vault.manifest.files.keys().collect();
+        entries.sort();
+
+        for vault_path in entries {
+            if !touches(&upstream_paths, vault_path) {
+                continue;
+            }
+            let changed_locally = report.modified.contains(vault_path) || touches(&local_paths, vault_path);
+            if changed_locally {
+                remote_status.conflicts.push(vault_path.clone());
+            } else {
+                remote_status.incoming.push(vault_path.clone());
+            }
+        }
+    }
+
+    report.remote_status = Some(remote_status);
+    Ok(report)
+}
+
+fn print_status(report: &StatusReport, remote: bool) {
+    // Display vault info
+    println!("{}", "Vault Status".bold());
+    println!("  Path: {}", report.path);
+
+    if let Some(ref primary) = report.primary_remote {
+        println!("  Remote: {} ({})", primary.url, primary.branch);
+        for mirror in &report.mirrors {
+            println!("  Mirror: {} {} ({})", mirror.name, mirror.url, mirror.branch);
+        }
+    } else {
+        println!("  Remote: {} (local-only mode)", "None".yellow());
+    }
+
+    println!("  Managed files: {}", report.managed_files);
+
+    if report.uncommitted_changes {
+        println!("\n{} Uncommitted changes in vault", "●".yellow().bold());
+        println!("  Run 'gfv backup' to commit changes");
+    } else {
+        println!("\n{} Vault is clean", "✓".green().bold());
+    }
+
+    println!("\n{}", "File Status:".bold());
+
+    if !report.modified.is_empty() {
         println!("\n{} Modified files:", "●".yellow().bold());
-        for file in &modified {
+        for file in &report.modified {
             println!("  {} {}", "M".yellow(), file);
         }
         println!("\n  Run 'gfv backup' to save changes");
     }
 
-    if !missing_source.is_empty() {
+    if !report.missing_source.is_empty() {
         println!("\n{} Missing source files:", "!".red().bold());
-        for file in &missing_source {
+        for file in &report.missing_source {
             println!("  {} {}", "?".red(), file);
         }
     }
 
-    if modified.is_empty() && missing_source.is_empty() {
+    if report.modified.is_empty() && report.missing_source.is_empty() {
         println!("\n{} All files are up to date", "✓".green().bold());
     }
 
-    Ok(())
+    if report.primary_remote.is_none() {
+        if remote {
+            println!("\n{} No remote configured - nothing to compare", "→".blue());
+        }
+        return;
+    }
+
+    let Some(ref status) = report.remote_status else {
+        if report.unpushed_commits == Some(true) {
+            println!("\n{} Unpushed commits (run 'gfv backup' to push)", "●".yellow().bold());
+        }
+        println!("\nRun 'gfv status --remote' to check for incoming changes");
+        return;
+    };
+
+    println!("\n{} {}/{}", "Remote Status:".bold(), status.remote, status.branch);
+
+    if !status.branch_exists {
+        println!("  {} Remote branch does not exist yet (run 'gfv backup' to push)", "→".blue());
+        return;
+    }
+
+    if status.ahead == 0 && status.behind == 0 {
+        println!("  {} Up to date with remote", "✓".green());
+        return;
+    }
+    if status.ahead > 0 {
+        println!("  {} {} {} ahead (run 'gfv backup' to push)",
+            "↑".yellow(), status.ahead, commits(status.ahead));
+    }
+    if status.behind > 0 {
+        println!("  {} {} {} behind (run 'gfv restore' to pull)",
+            "↓".yellow(), status.behind, commits(status.behind));
+    }
+
+    if !status.incoming.is_empty() || !status.conflicts.is_empty() {
+        println!("\n{}", "Incoming changes (not yet restored):".bold());
+        for file in &status.incoming {
+            println!("  {} {}", "↓".cyan(), file);
+        }
+        for file in &status.conflicts {
+            println!("  {} {} {}", "C".red().bold(), file, "(changed locally and upstream)".red());
+        }
+    }
+
+    if !status.conflicts.is_empty() {
+        println!("\n{} {} {} changed on both sides",
+            "!".red().bold(), status.conflicts.len(),
+            if status.conflicts.len() == 1 { "entry" } else { "entries" });
+        println!("  Back up or discard your local edits before running 'gfv restore'");
+    }
+}
+
+fn commits(count: usize) -> &'static str {
+    if count == 1 { "commit" } else { "commits" }
 }
@@ -0,0 +1,168 @@
+// Transfer command implementation - moves or copies an entry to another vault
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use std::fs;
+use std::path::Path;
+
+use crate::vault::Vault;
+use crate::git_ops::GitRepo;
+use super::helpers::{get_vault_dir, get_active_vault_name, display_path, is_within, copy_tree, remove_empty_parents};
+
+pub fn transfer(
+    file: String,
+    from: Option<String>,
+    to: String,
+    copy: bool,
+) -> Result<()> {
+    let from = from.unwrap_or_else(get_active_vault_name);
+    if from == to {
+        bail!("--from and --to are the same vault ('{}')", to);
+    }
+    let mut source = load_vault(&from)?;
+    let mut target = load_vault(&to)?;
+
+    let file = file.trim_end_matches('/').to_string();
+    let Some(entry) = source.manifest.get_file(&file).cloned() else {
+        bail!("File '{}' is not managed by vault '{}'.\n\nList managed files with: gfv list --vault {}", file, from, from);
+    };
+
+    // The entry keeps its vault path, so the target must have room for it
+    if let Some(existing) = target.manifest.get_file(&file) {
+        bail!("{} already exists in vault '{}' (linked from {})", file, to, existing.source_path);
+    }
+    if let Some(other) = target.manifest.files.keys()
+        .find(|other| is_within(&file, other) || is_within(other, &file))
+    {
+        bail!("{} overlaps {} in vault '{}'", file, other, to);
+    }
+    if target.get_file_path(&file).exists() {
+        bail!("{} already exists in the repo of vault '{}'", file, to);
+    }
+    if let Some((other, _)) = target.manifest.files.iter()
+        .find(|(_, other)| other.source_path == entry.source_path)
+    {
+        bail!("{} is already linked as {} in vault '{}'", display_path(Path::new(&entry.source_path)), other, to);
+    }
+
+    // The commits below must contain only the transfer, and a failed step must be
+    // able to put the repos back, so both start out clean
+    let source_repo = GitRepo::open(&source.repo_path)
+        .context("Failed to open git repository")?;
+    let target_repo = GitRepo::open(&target.repo_path)
+        .context("Failed to open git repository")?;
+    for (name, git_repo) in [(&from, &source_repo), (&to, &target_repo)] {
+        if git_repo.has_changes()? {
+            bail!("Vault '{}' has uncommitted changes. Run 'gfv backup --vault {}' first.", name, name);
+        }
+    }
+
+    let verb = if copy { "Copy" } else { "Transfer" };
+    println!("{} {} {} from vault '{}' to vault '{}'...", "==>".green().bold(),
+        if copy { "Copying" } else { "Transferring" }, file, from, to);
+
+    // Step 1: Add to the target vault and commit there; the entry is only saved once committed
+    let from_path = source.get_file_path(&file);
+    let to_path = target.get_file_path(&file);
+    let has_copy = from_path.exists();
+    let mut target_commit = None;
+    if has_copy {
+        let committed = copy_entry(&from_path, &to_path)
+            .and_then(|()| target_repo.stage_paths(&[&file]).context("Failed to stage changes"))
+            .and_then(|()| target_repo.commit(&format!("{} {} from vault '{}'", verb, file, from))
+                .context("Failed to commit changes"));
+        match committed {
+            Ok(commit_id) => target_commit = Some(commit_id.to_string()),
+            Err(e) => {
+                // Take the copy back out so the transfer can be retried
+                let _ = delete_entry(&to_path, &target.repo_path);
+                let _ = target_repo.stage_paths(&[&file]);
+                return Err(e.context(format!("Failed to add {} to vault '{}'; nothing was changed", file, to)));
+            }
+        }
+        println!("  {} Copied into vault '{}'", "✓".green(), to);
+        println!("  {} Committed in vault '{}'", "✓".green(), to);
+    } else {
+        println!("  {} Not in vault '{}' yet; only the entry is {}", "→".blue(), from,
+            if copy { "copied" } else { "transferred" });
+    }
+
+    target.manifest.add_file(file.clone(), entry.clone());
+    target.save_manifest()
+        .context("Failed to save manifest")?;
+
+    // Step 2: Remove from the source vault, pointing at the commit that took it over
+    if copy {
+        println!("  {} Kept in vault '{}'", "→".blue(), from);
+    } else {
+        if let Some(target_commit) = &target_commit {
+            let removed = delete_entry(&from_path, &source.repo_path)
+                .and_then(|()| source_repo.stage_paths(&[&file]).context("Failed to stage changes"))
+                .and_then(|()| source_repo.commit(&format!("Transfer {} to vault '{}'\n\nAdded there in {}",
+                    file, to, &target_commit[..7])).context("Failed to commit changes"));
+            if let Err(e) = removed {
+                // The target already has it committed; keep the source as it was
+                let _ = source_repo.checkout_path(&file);
+                return Err(e.context(format!(
+                    "{} was added to vault '{}' but could not be removed from vault '{}'\n\nRemove it there with: gfv unlink {} --delete-files --vault {}",
+                    file, to, from, file, from)));
+            }
+            println!("  {} Committed in vault '{}'", "✓".green(), from);
+        }
+
+        source.manifest.remove_file(&file);
+        source.save_manifest()
+            .context("Failed to save manifest")?;
+    }
+
+    let source_path = display_path(Path::new(&entry.source_path));
+    if copy {
+        println!("\n{} is now managed by vaults '{}' and '{}'", source_path, from, to);
+    } else {
+        println!("\n{} is now managed by vault '{}'", source_path, to);
+    }
+    if target_commit.is_some() {
+        println!("Push the changes with: gfv backup --vault {}{}", to,
+            if copy { String::new() } else { format!(" && gfv backup --vault {}", from) });
+    }
+
+    Ok(())
+}
+
+/// Load a vault by name, checking that it is initialized
+fn load_vault(name: &str) -> Result<Vault> {
+    let vault_dir = get_vault_dir(name)?;
+    if !Vault::is_initialized(&vault_dir) {
+        bail!("Vault '{}' not initialized. Run 'gfv init' first.", name);
+    }
+    Vault::load(&vault_dir)
+        .with_context(|| format!("Failed to load vault '{}'", name))
+}
+
+/// Copy an entry's file or directory to the same vault path in another repo
+fn copy_entry(from: &Path, to: &Path) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    if from.is_dir() {
+        copy_tree(from, to)
+    } else {
+        fs::copy(from, to)
+            .with_context(|| format!("Failed to copy {}", from.display()))?;
+        Ok(())
+    }
+}
+
+/// Delete an entry's file or directory from a repo, with directories left empty
+fn delete_entry(path: &Path, repo_path: &Path) -> Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)
+            .context("Failed to delete directory from vault")?;
+    } else if path.exists() {
+        fs::remove_file(path)
+            .context("Failed to delete file from vault")?;
+    }
+    remove_empty_parents(path, repo_path);
+    Ok(())
+}
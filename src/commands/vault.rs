@@ -2,14 +2,82 @@
 
 use anyhow::{bail, Context, Result};
 use colored::Colorize;
-use std::path::PathBuf;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
 
 use crate::config::Config;
+use crate::git_ops::AuthOptions;
+use crate::git_ops::bundle::BUNDLE_REMOTE;
+use crate::output::{self, OutputFormat};
 use crate::vault::Vault;
+use crate::vault::manifest::{RemoteConfig, DEFAULT_REMOTE};
+use super::helpers::{resolve_source_path, copy_tree};
 
-pub fn list() -> Result<()> {
+#[derive(Serialize)]
+struct VaultListOutput {
+    active: String,
+    vaults: Vec<VaultListEntry>,
+}
+
+#[derive(Serialize)]
+struct VaultListEntry {
+    name: String,
+    path: String,
+    active: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VaultInfoOutput {
+    name: String,
+    path: String,
+    active: bool,
+    initialized: bool,
+    primary_remote: Option<RemoteOutput>,
+    mirrors: Vec<RemoteOutput>,
+    managed_files: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RemoteOutput {
+    name: String,
+    url: String,
+    branch: String,
+    ssh_key: Option<String>,
+    token_env: Option<String>,
+}
+
+impl RemoteOutput {
+    fn new(name: &str, remote: &RemoteConfig) -> Self {
+        RemoteOutput {
+            name: name.to_string(),
+            url: remote.url.clone(),
+            branch: remote.branch.clone(),
+            ssh_key: remote.ssh_key.clone(),
+            token_env: remote.token_env.clone(),
+        }
+    }
+}
+
+pub fn list(format: OutputFormat) -> Result<()> {
     let config = load_config()?;
 
+    if format.is_json() {
+        let mut vaults: Vec<_> = config.vaults.iter()
+            .map(|(name, path)| VaultListEntry {
+                name: name.clone(),
+                path: path.clone(),
+                active: name == &config.current.active,
+            })
+            .collect();
+        vaults.sort_by(|a, b| a.name.cmp(&b.name));
+        return output::print_json(&VaultListOutput {
+            active: config.current.active.clone(),
+            vaults,
+        });
+    }
+
     if config.vaults.is_empty() {
         println!("No vaults found.");
         println!("\nCreate a vault with:");
@@ -33,8 +101,17 @@ pub fn list() -> Result<()> {
     Ok(())
 }
 
-pub fn create(name: String, path: Option<String>, remote: Option<String>, branch: Option<String>) -> Result<()> {
+pub fn create(
+    name: String,
+    path: Option<String>,
+    remote: Option<String>,
+    branch: Option<String>,
+    ssh_key: Option<String>,
+    token_env: Option<String>,
+) -> Result<()> {
     let mut config = load_config()?;
+    // Effective default branch (GFV_SYNC_DEFAULT_BRANCH > global > default)
+    let default_branch = Config::load_layered(None)?.sync.default_branch;
 
     // Check if vault already exists
     if config.vaults.contains_key(&name) {
@@ -45,10 +122,7 @@ pub fn create(name: String, path: Option<String>, remote: Option<String>, branch
     let vault_dir = if let Some(p) = path {
         PathBuf::from(p)
     } else {
-        dirs::home_dir()
-            .context("Failed to get home directory")?
-            .join(".gfv")
-            .join(&name)
+        crate::config::gfv_home()?.join(&name)
     };
 
     let repo_path = vault_dir.join("repo");
@@ -73,9 +147,18 @@ pub fn create(name: String, path: Option<String>, remote: Option<String>, branch
         // Check if remote is empty or has content by attempting to clone
         println!("{} Checking remote repository...", "==>".green().bold());
 
+        // Authentication settings are stored with the remote config below
+        let auth = AuthOptions::from_remote(&RemoteConfig {
+            url: remote_url.clone(),
+            branch: String::new(),
+            ssh_key: ssh_key.clone(),
+            token_env: token_env.clone(),
+        });
+
         // Try to clone into repo directory
-        match crate::git_ops::GitRepo::clone(&remote_url, &repo_path) {
-            Ok(git_repo) => {
+        match crate::git_ops::GitRepo::clone(&remote_url, &repo_path, auth.clone()) {
+            // Cloning an empty repository succeeds but leaves an unborn HEAD
+            Ok(git_repo) if !git_repo.is_empty().unwrap_or(true) => {
                 // Remote has content - use cloned repository
                 println!("{} Cloned existing vault from remote", "✓".green().bold());
 
@@ -97,10 +180,12 @@ pub fn create(name: String, path: Option<String>, remote: Option<String>, branch
                 let mut manifest = crate::vault::manifest::Manifest::load(&vault_dir)?;
 
                 // Ensure manifest has remote config
-                if manifest.remote.is_none() {
-                    manifest.remote = Some(crate::vault::manifest::RemoteConfig {
+                if !manifest.has_remote() {
+                    manifest.add_remote(DEFAULT_REMOTE.to_string(), RemoteConfig {
                         url: remote_url.clone(),
                         branch: selected_branch.clone(),
+                        ssh_key,
+                        token_env,
                     });
                     manifest.save(&vault_dir)
                         .context("Failed to save manifest")?;
@@ -124,8 +209,12 @@ pub fn create(name: String, path: Option<String>, remote: Option<String>, branch
                 if config.current.active == name {
                     println!("\n{} This is now the active vault", "→".blue());
                 }
+                if manifest.files.is_empty() {
+                    println!("{} Map the files in the repository to this machine with: gfv adopt --vault {}",
+                        "→".blue(), name);
+                }
             }
-            Err(_) => {
+            _ => {
                 // Remote is empty or doesn't exist - create new vault and push
                 println!("{} Remote is empty, creating new vault...", "==>".green().bold());
 
@@ -136,16 +225,19 @@ pub fn create(name: String, path: Option<String>, remote: Option<String>, branch
                 // Branch selection priority:
                 // 1. User specified --branch
                 // 2. Config default branch
-                let selected_branch = branch.unwrap_or_else(|| config.sync.default_branch.clone());
+                let selected_branch = branch.unwrap_or_else(|| default_branch.clone());
 
                 // Initialize Git repository
                 let git_repo = crate::git_ops::GitRepo::init(&repo_path)
-                    .context("Failed to initialize Git repository")?;
+                    .context("Failed to initialize Git repository")?
+                    .with_auth(auth);
 
                 // Create manifest with remote config
-                let remote_config = crate::vault::manifest::RemoteConfig {
+                let remote_config = RemoteConfig {
                     url: remote_url.clone(),
                     branch: selected_branch.clone(),
+                    ssh_key,
+                    token_env,
                 };
                 let manifest = crate::vault::manifest::Manifest::new(Some(remote_config));
 
@@ -168,9 +260,9 @@ pub fn create(name: String, path: Option<String>, remote: Option<String>, branch
                     .context("Failed to set branch name")?;
 
                 // Add remote and push
-                git_repo.add_remote("origin", &remote_url)
+                git_repo.add_remote(DEFAULT_REMOTE, &remote_url)
                     .context("Failed to add remote")?;
-                git_repo.push("origin", &selected_branch)
+                git_repo.push(DEFAULT_REMOTE, &selected_branch)
                     .context("Failed to push to remote")?;
 
                 println!("{} Vault '{}' initialized and pushed to remote!", "✓".green().bold(), name);
@@ -190,6 +282,10 @@ pub fn create(name: String, path: Option<String>, remote: Option<String>, branch
                 if config.current.active == name {
                     println!("\n{} This is now the active vault", "→".blue());
                 }
+                if manifest.files.is_empty() {
+                    println!("{} Map the files in the repository to this machine with: gfv adopt --vault {}",
+                        "→".blue(), name);
+                }
             }
         }
     } else {
@@ -199,7 +295,7 @@ pub fn create(name: String, path: Option<String>, remote: Option<String>, branch
         // Branch selection priority:
         // 1. User specified --branch
         // 2. Config default branch
-        let selected_branch = branch.unwrap_or_else(|| config.sync.default_branch.clone());
+        let selected_branch = branch.unwrap_or_else(|| default_branch.clone());
 
         // Initialize Git repository
         let git_repo = crate::git_ops::GitRepo::init(&repo_path)
@@ -325,7 +421,174 @@ pub fn remove(name: String, delete_files: bool) -> Result<()> {
     Ok(())
 }
 
-pub fn info(name: Option<String>) -> Result<()> {
+pub fn rename(old: String, new: String) -> Result<()> {
+    let mut config = load_config()?;
+
+    if !config.vaults.contains_key(&old) {
+        bail!("Vault '{}' not found.\n\nList available vaults with:\n  gfv vault list", old);
+    }
+    if new.trim().is_empty() {
+        bail!("Vault name cannot be empty");
+    }
+    if config.vaults.contains_key(&new) {
+        bail!("Vault '{}' already exists at: {}", new, config.vaults[&new]);
+    }
+
+    let vault_path = config.vaults.remove(&old).unwrap();
+    config.vaults.insert(new.clone(), vault_path.clone());
+    let active = config.current.active == old;
+    if active {
+        config.current.active = new.clone();
+    }
+
+    // Aliases that name the vault, e.g. `backup --vault <old>` or `vault switch <old>`
+    let mut updated_aliases = Vec::new();
+    for (alias, command) in config.aliases.iter_mut() {
+        if let Some(renamed) = rename_in_command(command, &old, &new) {
+            *command = renamed;
+            updated_aliases.push((alias.clone(), command.clone()));
+        }
+    }
+
+    updated_aliases.sort();
+    save_config(&config)?;
+
+    println!("{} Renamed vault '{}' to '{}'", "✓".green().bold(), old, new);
+    if active {
+        println!("  {} It is still the active vault", "→".blue());
+    }
+    for (alias, command) in &updated_aliases {
+        println!("  {} Updated alias '{}' → '{}'", "→".blue(), alias, command);
+    }
+
+    // The directory keeps its name; point that out when it was the default one
+    let default_dir = crate::config::gfv_home()?.join(&old);
+    if Path::new(&vault_path) == default_dir {
+        println!("\nThe vault directory is still {}", vault_path);
+        println!("Move it with:\n  gfv vault move {} {}", new,
+            crate::config::gfv_home()?.join(&new).display());
+    }
+
+    Ok(())
+}
+
+/// Replace vault name `old` in an alias command where it is used as a vault name:
+/// after `--vault`, after `--name`/`-n` of the commands where that names the vault
+/// (elsewhere, as in `link`, it is a vault path), or as the name argument of
+/// `vault switch/info/remove/move/rename`
+fn rename_in_command(command: &str, old: &str, new: &str) -> Option<String> {
+    const NAME_SUBCOMMANDS: [&str; 5] = ["switch", "info", "remove", "move", "rename"];
+    const NAME_OPTION_COMMANDS: [&[&str]; 4] = [
+        &["init"], &["vault", "set-remote"], &["vault", "set-branch"], &["vault", "remove-remote"],
+    ];
+
+    let mut words: Vec<String> = command.split_whitespace().map(str::to_string).collect();
+    let name_is_vault = NAME_OPTION_COMMANDS.iter()
+        .any(|prefix| words.len() >= prefix.len() && prefix.iter().zip(&words).all(|(a, b)| a == b));
+    let mut options = vec!["--vault"];
+    if name_is_vault {
+        options.extend(["--name", "-n"]);
+    }
+
+    let mut changed = false;
+    for i in 0..words.len() {
+        let renamed = if words[i] == old {
+            (i > 0 && options.contains(&words[i - 1].as_str()))
+                || (i == 2 && words[0] == "vault" && NAME_SUBCOMMANDS.contains(&words[1].as_str()))
+        } else {
+            false
+        };
+        if renamed {
+            words[i] = new.to_string();
+            changed = true;
+            continue;
+        }
+        for option in options.iter().filter(|option| option.starts_with("--")) {
+            if words[i].strip_prefix(option).and_then(|rest| rest.strip_prefix('=')) == Some(old) {
+                words[i] = format!("{}={}", option, new);
+                changed = true;
+            }
+        }
+    }
+
+    changed.then(|| words.join(" "))
+}
+
+pub fn move_vault(name: String, path: String) -> Result<()> {
+    let mut config = load_config()?;
+
+    if !config.vaults.contains_key(&name) {
+        bail!("Vault '{}' not found.\n\nList available vaults with:\n  gfv vault list", name);
+    }
+    let old_dir = PathBuf::from(&config.vaults[&name]);
+    if !old_dir.is_dir() {
+        bail!("Vault directory {} does not exist.\n\nIf it was moved by hand, 'gfv doctor' can help", old_dir.display());
+    }
+
+    // Resolved through symlinks and `..`, so the check below sees where it really goes
+    let new_dir = canonicalize_new(&resolve_source_path(&path)?)?;
+    if new_dir.exists() && std::fs::read_dir(&new_dir).map(|mut dir| dir.next().is_some()).unwrap_or(true) {
+        bail!("{} already exists and is not empty", new_dir.display());
+    }
+    let old_dir = old_dir.canonicalize().unwrap_or(old_dir);
+    if new_dir.starts_with(&old_dir) {
+        bail!("Cannot move vault '{}' into itself", name);
+    }
+
+    println!("{} Moving vault '{}'...", "==>".green().bold(), name);
+    println!("  From: {}", old_dir.display());
+    println!("  To:   {}", new_dir.display());
+
+    if let Some(parent) = new_dir.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    if new_dir.exists() {
+        // Only an empty directory gets here; rename can't replace it on every platform
+        std::fs::remove_dir(&new_dir)?;
+    }
+
+    // A rename within the same file system, otherwise copy, check and delete
+    let renamed = match std::fs::rename(&old_dir, &new_dir) {
+        Ok(()) => true,
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => false,
+        Err(e) => {
+            return Err(anyhow::Error::new(e)
+                .context(format!("Failed to move {} to {}", old_dir.display(), new_dir.display())));
+        }
+    };
+    if !renamed {
+        println!("  {} Copying to another file system...", "→".blue());
+        if let Err(e) = copy_tree(&old_dir, &new_dir) {
+            std::fs::remove_dir_all(&new_dir).ok();
+            return Err(e.context("Failed to copy vault; nothing was changed"));
+        }
+        config.vaults.insert(name.clone(), new_dir.display().to_string());
+        save_config(&config)?;
+        std::fs::remove_dir_all(&old_dir)
+            .with_context(|| format!("Vault copied, but failed to remove {}", old_dir.display()))?;
+    } else {
+        config.vaults.insert(name.clone(), new_dir.display().to_string());
+        save_config(&config)?;
+    }
+
+    println!("{} Moved vault '{}' to {}", "✓".green().bold(), name, new_dir.display());
+    Ok(())
+}
+
+/// Canonical form of a path that may not exist yet: its nearest existing
+/// ancestor is canonicalized and the rest appended
+fn canonicalize_new(path: &Path) -> Result<PathBuf> {
+    for ancestor in path.ancestors() {
+        if let Ok(canonical) = ancestor.canonicalize() {
+            let rest = path.strip_prefix(ancestor)?;
+            return Ok(canonical.join(rest));
+        }
+    }
+    Ok(path.to_path_buf())
+}
+
+pub fn info(name: Option<String>, format: OutputFormat) -> Result<()> {
     let config = load_config()?;
 
     let vault_name = name.unwrap_or_else(|| config.current.active.clone());
@@ -338,6 +601,30 @@ pub fn info(name: Option<String>) -> Result<()> {
     let vault_path = PathBuf::from(&config.vaults[&vault_name]);
     let is_active = vault_name == config.current.active;
 
+    if format.is_json() {
+        let mut info = VaultInfoOutput {
+            name: vault_name.clone(),
+            path: vault_path.display().to_string(),
+            active: is_active,
+            initialized: Vault::is_initialized(&vault_path),
+            primary_remote: None,
+            mirrors: Vec::new(),
+            managed_files: 0,
+        };
+        if info.initialized {
+            let vault = Vault::load(&vault_path)?;
+            let primary = vault.manifest.primary_remote().map(|(name, _)| name);
+            info.primary_remote = vault.manifest.primary_remote()
+                .map(|(name, remote)| RemoteOutput::new(name, remote));
+            info.mirrors = vault.manifest.remotes.iter()
+                .filter(|(name, _)| Some(name.as_str()) != primary)
+                .map(|(name, remote)| RemoteOutput::new(name, remote))
+                .collect();
+            info.managed_files = vault.manifest.files.len();
+        }
+        return output::print_json(&info);
+    }
+
     println!("Vault: {} {}",
         vault_name.cyan().bold(),
         if is_active { "(active)".green() } else { "".normal() }
@@ -348,9 +635,25 @@ pub fn info(name: Option<String>) -> Result<()> {
     if Vault::is_initialized(&vault_path) {
         let vault = Vault::load(&vault_path)?;
 
-        if let Some(remote) = &vault.manifest.remote {
+        if let Some((primary, remote)) = vault.manifest.primary_remote() {
             println!("Remote: {}", remote.url);
             println!("Branch: {}", remote.branch);
+            if let Some(ref key) = remote.ssh_key {
+                println!("SSH key: {}", key);
+            }
+            if let Some(ref var) = remote.token_env {
+                println!("Token env: ${}", var);
+            }
+
+            let mirrors: Vec<_> = vault.manifest.remotes.iter()
+                .filter(|(name, _)| name.as_str() != primary)
+                .collect();
+            if !mirrors.is_empty() {
+                println!("Mirrors:");
+                for (name, mirror) in mirrors {
+                    println!("  {} {} ({})", name, mirror.url, mirror.branch);
+                }
+            }
         } else {
             println!("Remote: (none)");
         }
@@ -364,7 +667,13 @@ pub fn info(name: Option<String>) -> Result<()> {
     Ok(())
 }
 
-pub fn set_remote(url: String, branch: Option<String>, name: Option<String>) -> Result<()> {
+pub fn set_remote(
+    url: String,
+    branch: Option<String>,
+    name: Option<String>,
+    ssh_key: Option<String>,
+    token_env: Option<String>,
+) -> Result<()> {
     let config = load_config()?;
     let vault_name = name.unwrap_or_else(|| config.current.active.clone());
 
@@ -378,7 +687,7 @@ pub fn set_remote(url: String, branch: Option<String>, name: Option<String>) ->
     // Determine branch: use provided, or keep existing, or default to "main"
     let branch_name = if let Some(b) = branch {
         b
-    } else if let Some(ref remote) = vault.manifest.remote {
+    } else if let Some((_, remote)) = vault.manifest.primary_remote() {
         remote.branch.clone()
     } else {
         // Try to get current branch from git repo
@@ -386,21 +695,43 @@ pub fn set_remote(url: String, branch: Option<String>, name: Option<String>) ->
         git_repo.current_branch().unwrap_or_else(|_| "main".to_string())
     };
 
+    // This command manages the primary remote ("origin" for new vaults)
+    let remote_name = vault.manifest.primary_remote()
+        .map(|(name, _)| name.to_string())
+        .unwrap_or_else(|| DEFAULT_REMOTE.to_string());
+
+    // Keep existing auth settings unless new ones are given
+    let (ssh_key, token_env) = match vault.manifest.remotes.get(&remote_name) {
+        Some(existing) => (
+            ssh_key.or_else(|| existing.ssh_key.clone()),
+            token_env.or_else(|| existing.token_env.clone()),
+        ),
+        None => (ssh_key, token_env),
+    };
+
     // Update remote in manifest
-    vault.manifest.remote = Some(crate::vault::manifest::RemoteConfig {
+    vault.manifest.add_remote(remote_name.clone(), RemoteConfig {
         url: url.clone(),
         branch: branch_name.clone(),
+        ssh_key: ssh_key.clone(),
+        token_env: token_env.clone(),
     });
 
     vault.save_manifest()?;
 
     // Also set git remote
     let git_repo = crate::git_ops::GitRepo::open(&vault.repo_path)?;
-    git_repo.set_remote("origin", &url)?;
+    git_repo.set_remote(&remote_name, &url)?;
 
     println!("{} Set remote for vault '{}'", "✓".green().bold(), vault_name);
     println!("  URL: {}", url);
     println!("  Branch: {}", branch_name);
+    if let Some(key) = ssh_key {
+        println!("  SSH key: {}", key);
+    }
+    if let Some(var) = token_env {
+        println!("  Token env: ${}", var);
+    }
 
     Ok(())
 }
@@ -417,7 +748,7 @@ pub fn set_branch(branch: String, name: Option<String>) -> Result<()> {
     let mut vault = Vault::load(&vault_path)?;
 
     // Check if remote is configured
-    if let Some(ref mut remote) = vault.manifest.remote {
+    if let Some(remote) = vault.manifest.primary_remote_mut() {
         remote.branch = branch.clone();
         vault.save_manifest()?;
 
@@ -428,7 +759,7 @@ pub fn set_branch(branch: String, name: Option<String>) -> Result<()> {
         println!("{} Updated branch for vault '{}'", "✓".green().bold(), vault_name);
         println!("  Branch: {}", branch);
     } else {
-        bail!("No remote configured for vault '{}'\n\nSet remote first with:\n  gfv vault remote set <url>", vault_name);
+        bail!("No remote configured for vault '{}'\n\nSet remote first with:\n  gfv vault set-remote <url>", vault_name);
     }
 
     Ok(())
@@ -445,19 +776,159 @@ pub fn remove_remote(name: Option<String>) -> Result<()> {
     let vault_path = PathBuf::from(&config.vaults[&vault_name]);
     let mut vault = Vault::load(&vault_path)?;
 
-    if vault.manifest.remote.is_none() {
+    let Some(remote_name) = vault.manifest.primary_remote().map(|(name, _)| name.to_string()) else {
         println!("No remote configured for vault '{}'", vault_name);
         return Ok(());
-    }
+    };
 
-    vault.manifest.remote = None;
+    vault.manifest.remove_remote(&remote_name);
     vault.save_manifest()?;
 
+    let git_repo = crate::git_ops::GitRepo::open(&vault.repo_path)?;
+    git_repo.remove_remote(&remote_name)?;
+
     println!("{} Removed remote for vault '{}'", "✓".green().bold(), vault_name);
+    if let Some((primary, _)) = vault.manifest.primary_remote() {
+        println!("  Primary remote is now '{}'", primary);
+    }
+
+    Ok(())
+}
+
+pub fn remote_add(
+    name: String,
+    url: String,
+    branch: Option<String>,
+    primary: bool,
+    ssh_key: Option<String>,
+    token_env: Option<String>,
+    vault: Option<String>,
+) -> Result<()> {
+    let (vault_name, mut vault) = load_vault(vault)?;
+
+    if name == BUNDLE_REMOTE {
+        bail!("'{}' is reserved for applied bundles; choose another remote name", name);
+    }
+    if !git2::Remote::is_valid_name(&name) {
+        bail!("'{}' is not a valid remote name", name);
+    }
+    if vault.manifest.remotes.contains_key(&name) {
+        bail!(
+            "Remote '{}' already exists in vault '{}'\n\nRemove it first with:\n  gfv vault remote remove {}",
+            name, vault_name, name
+        );
+    }
+
+    let git_repo = crate::git_ops::GitRepo::open(&vault.repo_path)?;
+
+    // Mirrors default to the branch the vault is on
+    let branch_name = match branch {
+        Some(b) => b,
+        None => git_repo.current_branch().unwrap_or_else(|_| "main".to_string()),
+    };
+
+    vault.manifest.add_remote(name.clone(), RemoteConfig {
+        url: url.clone(),
+        branch: branch_name.clone(),
+        ssh_key,
+        token_env,
+    });
+    if primary {
+        vault.manifest.primary = Some(name.clone());
+    }
+    vault.save_manifest()?;
+
+    git_repo.set_remote(&name, &url)?;
+
+    println!("{} Added remote '{}' to vault '{}'", "✓".green().bold(), name, vault_name);
+    println!("  URL: {}", url);
+    println!("  Branch: {}", branch_name);
+    if vault.manifest.primary.as_deref() == Some(name.as_str()) {
+        println!("  {} This is the primary remote", "→".blue());
+    }
+
+    Ok(())
+}
+
+pub fn remote_remove(name: String, vault: Option<String>) -> Result<()> {
+    let (vault_name, mut vault) = load_vault(vault)?;
+
+    if vault.manifest.remove_remote(&name).is_none() {
+        bail!("Remote '{}' not found in vault '{}'\n\nList remotes with:\n  gfv vault remote list", name, vault_name);
+    }
+    vault.save_manifest()?;
+
+    let git_repo = crate::git_ops::GitRepo::open(&vault.repo_path)?;
+    git_repo.remove_remote(&name)?;
+
+    println!("{} Removed remote '{}' from vault '{}'", "✓".green().bold(), name, vault_name);
+    if let Some((primary, _)) = vault.manifest.primary_remote() {
+        println!("  Primary remote: {}", primary);
+    }
 
     Ok(())
 }
 
+pub fn remote_list(vault: Option<String>) -> Result<()> {
+    let (vault_name, vault) = load_vault(vault)?;
+
+    let Some((primary, _)) = vault.manifest.primary_remote() else {
+        println!("No remotes configured for vault '{}'", vault_name);
+        println!("\nAdd one with:");
+        println!("  gfv vault remote add <name> <url>");
+        return Ok(());
+    };
+
+    for (name, remote) in &vault.manifest.remotes {
+        let is_primary = name == primary;
+        let marker = if is_primary { "*" } else { " " };
+        let status = if is_primary { "(primary)".green() } else { "".normal() };
+
+        println!("{} {:<12} {} [{}] {}",
+            marker.green().bold(),
+            name,
+            remote.url,
+            remote.branch,
+            status
+        );
+    }
+
+    Ok(())
+}
+
+pub fn remote_set_primary(name: String, vault: Option<String>) -> Result<()> {
+    let (vault_name, mut vault) = load_vault(vault)?;
+
+    if !vault.manifest.remotes.contains_key(&name) {
+        bail!("Remote '{}' not found in vault '{}'", name, vault_name);
+    }
+
+    vault.manifest.primary = Some(name.clone());
+    vault.save_manifest()?;
+
+    println!("{} '{}' is now the primary remote of vault '{}'", "✓".green().bold(), name, vault_name);
+    println!("Restore pulls from it; backup pushes to it and every mirror.");
+
+    Ok(())
+}
+
+/// Load an initialized vault by name (default: active vault)
+fn load_vault(name: Option<String>) -> Result<(String, Vault)> {
+    let config = load_config()?;
+    let vault_name = name.unwrap_or_else(|| config.current.active.clone());
+
+    if !config.vaults.contains_key(&vault_name) {
+        bail!("Vault '{}' not found", vault_name);
+    }
+
+    let vault_path = PathBuf::from(&config.vaults[&vault_name]);
+    if !Vault::is_initialized(&vault_path) {
+        bail!("Vault '{}' is not initialized", vault_name);
+    }
+
+    Ok((vault_name, Vault::load(&vault_path)?))
+}
+
 fn load_config() -> Result<Config> {
     Config::load()
 }
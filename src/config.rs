@@ -1,19 +1,58 @@
 // Config module - manages global configuration
 
+pub mod settings;
+
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::OnceLock;
+use toml::Value;
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Environment variable that relocates the gfv home directory (default: ~/.gfv)
+pub const GFV_HOME_ENV: &str = "GFV_HOME";
+
+/// Config file given with the global `--config` option
+static CONFIG_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Use `path` as the config file for this process instead of `<gfv home>/config.toml`
+pub fn set_config_override(path: PathBuf) {
+    let _ = CONFIG_OVERRIDE.set(path);
+}
+
+/// Resolve the gfv home directory: `$GFV_HOME` if set, otherwise `~/.gfv`.
+/// Every gfv path (config file, default vault locations) derives from this.
+pub fn gfv_home() -> Result<PathBuf> {
+    if let Some(home) = std::env::var_os(GFV_HOME_ENV).filter(|v| !v.is_empty()) {
+        let home = PathBuf::from(home);
+        if home.is_absolute() {
+            return Ok(home);
+        }
+        let cwd = std::env::current_dir()
+            .context("Failed to get current directory")?;
+        return Ok(cwd.join(home));
+    }
+
+    let home = dirs::home_dir()
+        .context("Failed to get home directory")?;
+    Ok(home.join(".gfv"))
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Config {
+    #[serde(default)]
     pub vaults: HashMap<String, String>,
+    #[serde(default)]
     pub current: CurrentConfig,
     #[serde(default)]
     pub ai: AiConfig,
     #[serde(default)]
     pub sync: SyncConfig,
     #[serde(default)]
+    pub ui: UiConfig,
+    #[serde(default)]
+    pub backup: BackupConfig,
+    #[serde(default)]
     pub aliases: HashMap<String, String>,
 }
 
@@ -22,12 +61,40 @@ pub struct CurrentConfig {
     pub active: String,
 }
 
+impl Default for CurrentConfig {
+    fn default() -> Self {
+        Self {
+            active: "default".to_string(),
+        }
+    }
+}
+
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct AiConfig {
     pub provider: Option<String>,
     pub api_key: Option<String>,
+    /// Environment variable holding the API key
+    pub api_key_env: Option<String>,
+    /// Command whose output is the API key (e.g. `pass show openai`)
+    pub api_key_cmd: Option<String>,
     pub model: Option<String>,
     pub endpoint: Option<String>,
+    /// Request timeout in seconds (default depends on the provider)
+    pub timeout: Option<u64>,
+    /// Approximate size of the diff summary sent to the AI, in tokens
+    pub token_budget: Option<usize>,
+    /// Prompt template file, relative to the vault repo unless absolute
+    pub prompt_file: Option<String>,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+    /// Ask for a multi-line message (subject, blank line, bullet points)
+    #[serde(default)]
+    pub body: bool,
+    /// Maximum subject line length
+    pub subject_length: Option<usize>,
+    /// Glob patterns (vault paths) never sent to the AI provider
+    #[serde(default)]
+    pub exclude: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -47,34 +114,87 @@ impl Default for SyncConfig {
     }
 }
 
-fn default_conflict_strategy() -> String {
-    "prompt".to_string()
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupConfig {
+    /// Glob patterns matched against vault paths, skipped when copying into the vault
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    /// Template for commit messages written without AI (see `vault::commit_message`)
+    #[serde(default = "default_message_template")]
+    pub message_template: String,
+    /// plain | conventional
+    #[serde(default = "default_message_style")]
+    pub message_style: String,
 }
 
-fn default_branch() -> String {
-    "main".to_string()
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            ignore: Vec::new(),
+            message_template: default_message_template(),
+            message_style: default_message_style(),
+        }
+    }
 }
 
-impl Default for Config {
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UiConfig {
+    /// auto | always | never
+    #[serde(default = "default_color")]
+    pub color: String,
+    #[serde(default = "default_true")]
+    pub emoji: bool,
+}
+
+impl Default for UiConfig {
     fn default() -> Self {
         Self {
-            vaults: HashMap::new(),
-            current: CurrentConfig {
-                active: "default".to_string(),
-            },
-            ai: Default::default(),
-            sync: Default::default(),
-            aliases: HashMap::new(),
+            color: default_color(),
+            emoji: true,
         }
     }
 }
 
+fn default_color() -> String {
+    "auto".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_message_template() -> String {
+    crate::vault::commit_message::DEFAULT_TEMPLATE.to_string()
+}
+
+fn default_message_style() -> String {
+    "plain".to_string()
+}
+
+fn default_conflict_strategy() -> String {
+    "prompt".to_string()
+}
+
+fn default_branch() -> String {
+    "main".to_string()
+}
+
 impl Config {
-    /// Get the config file path
+    /// Get the config file path (`--config`, or `config.toml` in the gfv home)
     pub fn config_path() -> Result<PathBuf> {
-        let home = dirs::home_dir()
-            .context("Failed to get home directory")?;
-        Ok(home.join(".gfv").join("config.toml"))
+        if let Some(path) = CONFIG_OVERRIDE.get() {
+            return Ok(path.clone());
+        }
+        Ok(gfv_home()?.join("config.toml"))
+    }
+
+    /// Get the credentials file path (`credentials.toml` next to the config file).
+    /// It holds secret keys only and is kept readable by the owner alone.
+    pub fn credentials_path() -> Result<PathBuf> {
+        let config_path = Self::config_path()?;
+        let dir = config_path.parent()
+            .context("Config file has no parent directory")?;
+        Ok(dir.join("credentials.toml"))
     }
 
     /// Load config from file, or return default if not exists
@@ -83,15 +203,7 @@ impl Config {
 
         if !config_path.exists() {
             // Return default config
-            return Ok(Self {
-                vaults: HashMap::new(),
-                current: CurrentConfig {
-                    active: "default".to_string(),
-                },
-                ai: Default::default(),
-                sync: Default::default(),
-                aliases: HashMap::new(),
-            });
+            return Ok(Self::default());
         }
 
         let content = std::fs::read_to_string(&config_path)
@@ -103,38 +215,46 @@ impl Config {
         Ok(config)
     }
 
-    /// Save config to file
+    /// Load the effective settings for a vault: env > vault > global > defaults.
+    /// Read-only view - never save the result, it would flatten the layers.
+    pub fn load_layered(vault_dir: Option<&std::path::Path>) -> Result<Self> {
+        settings::Layers::load(vault_dir)?.merged()
+    }
+
+    /// Load the effective settings for the active vault
+    pub fn load_effective() -> Result<Self> {
+        let global = Self::load()?;
+        let vault_dir = global.get_vault_dir(&global.current.active);
+        Self::load_layered(vault_dir.as_deref())
+    }
+
+    /// Save the vaults, the active vault and the aliases. Other settings in the file
+    /// belong to `gfv config` and are left as they are, so no defaults are written.
     pub fn save(&self) -> Result<()> {
         let config_path = Self::config_path()?;
+        let mut table = settings::read_table(&config_path)?;
 
-        // Ensure directory exists
-        if let Some(parent) = config_path.parent() {
-            std::fs::create_dir_all(parent)
-                .context("Failed to create config directory")?;
+        for (key, value) in [
+            ("vaults", Value::try_from(&self.vaults)),
+            ("current", Value::try_from(&self.current)),
+            ("aliases", Value::try_from(&self.aliases)),
+        ] {
+            let value = value.context("Failed to serialize config")?;
+            match value {
+                Value::Table(section) if section.is_empty() => {
+                    table.remove(key);
+                }
+                value => {
+                    table.insert(key.to_string(), value);
+                }
+            }
         }
 
-        let content = toml::to_string_pretty(self)
-            .context("Failed to serialize config")?;
-
-        std::fs::write(&config_path, content)
-            .context("Failed to write config file")?;
-
-        Ok(())
-    }
-
-    /// Get the current active vault directory
-    pub fn get_active_vault_dir(&self) -> Result<PathBuf> {
-        let vault_path = self.vaults.get(&self.current.active)
-            .ok_or_else(|| anyhow::anyhow!(
-                "Active vault '{}' not found in config",
-                self.current.active
-            ))?;
-
-        Ok(PathBuf::from(vault_path))
+        settings::write_table(&config_path, &table)
     }
 
     /// Get a specific vault directory by name
     pub fn get_vault_dir(&self, name: &str) -> Option<PathBuf> {
-        self.vaults.get(name).map(|path| PathBuf::from(path))
+        self.vaults.get(name).map(PathBuf::from)
     }
 }
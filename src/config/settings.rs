@@ -0,0 +1,422 @@
+// Settings module - layered configuration keys
+//
+// Effective settings are merged from six layers, highest first:
+//   env (GFV_<SECTION>_<KEY>) > vault (<vault_dir>/config.toml)
+//   > vault-shared (repo/.gfv.toml, committed) > credentials (credentials.toml, secrets only)
+//   > global (config.toml) > defaults
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use toml::{Table, Value};
+
+use super::Config;
+
+/// File holding per-vault settings, next to the manifest
+pub const VAULT_CONFIG_FILE: &str = "config.toml";
+
+/// Settings file committed at the root of the vault repo, shared by every clone
+pub const SHARED_CONFIG_FILE: &str = ".gfv.toml";
+
+/// Where a setting value came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin {
+    Default,
+    Global,
+    /// `credentials.toml` next to the global config
+    Credentials,
+    /// `.gfv.toml` in the vault repo
+    Shared,
+    Vault,
+    /// Environment variable name
+    Env(String),
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::Default => write!(f, "default"),
+            Origin::Global => write!(f, "global"),
+            Origin::Credentials => write!(f, "credentials"),
+            Origin::Shared => write!(f, "vault-shared"),
+            Origin::Vault => write!(f, "vault"),
+            Origin::Env(var) => write!(f, "env:{}", var),
+        }
+    }
+}
+
+/// Value type of a setting, used to validate input
+#[derive(Debug, Clone, Copy)]
+pub enum Kind {
+    Str,
+    Bool,
+    /// Non-negative integer
+    Int,
+    /// Non-negative number
+    Float,
+    Choice(&'static [&'static str]),
+    /// List of strings, comma-separated on the command line
+    List,
+}
+
+/// A known configuration key
+pub struct KeyDef {
+    pub key: &'static str,
+    pub kind: Kind,
+    /// Only allowed in the global config (not per vault, not shared, not from env)
+    pub global_only: bool,
+    /// Read from the shared `.gfv.toml`; anyone who can push to the vault repo edits that file,
+    /// so only keys that cannot redirect requests or credentials are shared
+    pub shared: bool,
+    /// Never printed in full
+    pub secret: bool,
+    pub description: &'static str,
+}
+
+/// All settable keys, in display order. `aliases.<name>` keys are handled separately.
+pub const KEYS: &[KeyDef] = &[
+    KeyDef { key: "ai.provider", kind: Kind::Choice(&["openai", "anthropic", "ollama"]),
+        global_only: false, shared: false, secret: false, description: "AI provider (default: openai)" },
+    KeyDef { key: "ai.endpoint", kind: Kind::Str, global_only: false, shared: false, secret: false,
+        description: "API endpoint URL (default depends on the provider)" },
+    KeyDef { key: "ai.api_key", kind: Kind::Str, global_only: true, shared: false, secret: true,
+        description: "API key for authentication (prefer --credentials, api_key_env or api_key_cmd)" },
+    KeyDef { key: "ai.api_key_env", kind: Kind::Str, global_only: true, shared: false, secret: false,
+        description: "Environment variable holding the API key" },
+    KeyDef { key: "ai.api_key_cmd", kind: Kind::Str, global_only: true, shared: false, secret: false,
+        description: "Command that prints the API key (e.g. 'pass show openai')" },
+    KeyDef { key: "ai.model", kind: Kind::Str, global_only: false, shared: true, secret: false,
+        description: "Model name (default depends on the provider)" },
+    KeyDef { key: "ai.timeout", kind: Kind::Int, global_only: false, shared: true, secret: false,
+        description: "Request timeout in seconds" },
+    KeyDef { key: "ai.token_budget", kind: Kind::Int, global_only: false, shared: true, secret: false,
+        description: "Approximate size of the diff summary sent to the AI, in tokens (default: 3000)" },
+    KeyDef { key: "ai.prompt_file", kind: Kind::Str, global_only: false, shared: true, secret: false,
+        description: "Prompt template file ({diff} {files} {host} {vault} {max_subject}); relative to the vault repo" },
+    KeyDef { key: "ai.temperature", kind: Kind::Float, global_only: false, shared: true, secret: false,
+        description: "Sampling temperature (default: 0.7)" },
+    KeyDef { key: "ai.max_tokens", kind: Kind::Int, global_only: false, shared: true, secret: false,
+        description: "Maximum length of the response in tokens (default: 100, or 300 with ai.body)" },
+    KeyDef { key: "ai.body", kind: Kind::Bool, global_only: false, shared: true, secret: false,
+        description: "Ask for a body with bullet points after the subject line" },
+    KeyDef { key: "ai.subject_length", kind: Kind::Int, global_only: false, shared: true, secret: false,
+        description: "Maximum subject line length; longer subjects are shortened (default: 72)" },
+    KeyDef { key: "ai.exclude", kind: Kind::List, global_only: false, shared: true, secret: false,
+        description: "Glob patterns (vault paths) never sent to the AI provider" },
+    KeyDef { key: "sync.conflict_strategy", kind: Kind::Choice(&["prompt", "use_vault", "use_source"]),
+        global_only: false, shared: true, secret: false, description: "Conflict resolution strategy" },
+    KeyDef { key: "sync.default_branch", kind: Kind::Str, global_only: false, shared: true, secret: false,
+        description: "Branch used when a remote has none" },
+    KeyDef { key: "ui.color", kind: Kind::Choice(&["auto", "always", "never"]),
+        global_only: false, shared: false, secret: false, description: "Colored output" },
+    KeyDef { key: "ui.emoji", kind: Kind::Bool, global_only: false, shared: false, secret: false,
+        description: "Use emoji in terminal output" },
+    KeyDef { key: "backup.ignore", kind: Kind::List, global_only: false, shared: true, secret: false,
+        description: "Glob patterns (vault paths) never copied into the vault" },
+    KeyDef { key: "backup.message_template", kind: Kind::Str, global_only: false, shared: true, secret: false,
+        description: "Commit message template used without AI ({verb} {paths} {counts} {added} {modified} {removed} {total})" },
+    KeyDef { key: "backup.message_style", kind: Kind::Choice(&["plain", "conventional"]),
+        global_only: false, shared: true, secret: false, description: "Commit message style used without AI" },
+];
+
+/// Prefix of alias keys (`aliases.<name>`)
+pub const ALIAS_PREFIX: &str = "aliases.";
+
+/// Look up a key definition. Alias keys get a synthetic global-only string definition.
+pub fn key_def(key: &str) -> Option<&'static KeyDef> {
+    const ALIAS: KeyDef = KeyDef { key: "aliases.<name>", kind: Kind::Str, global_only: true, shared: false,
+        secret: false, description: "Command alias" };
+
+    if let Some(name) = key.strip_prefix(ALIAS_PREFIX) {
+        return (!name.is_empty() && !name.contains('.')).then_some(&ALIAS);
+    }
+    KEYS.iter().find(|def| def.key == key)
+}
+
+/// Validate a key for writing and return its definition
+pub fn writable_key(key: &str) -> Result<&'static KeyDef> {
+    match key_def(key) {
+        Some(def) => Ok(def),
+        None if key.starts_with("vault.") || key.starts_with("remote.") => {
+            bail!("'{}' is read-only. Use 'gfv vault' commands to change vaults and remotes", key)
+        }
+        None => bail!("Unknown configuration key: {}\n\nRun 'gfv config --list' to see all keys", key),
+    }
+}
+
+/// Environment variable that overrides a key: `ai.model` → `GFV_AI_MODEL`
+pub fn env_var(key: &str) -> String {
+    format!("GFV_{}", key.to_uppercase().replace('.', "_"))
+}
+
+/// Parse user input into a typed value for the key
+pub fn parse_value(def: &KeyDef, key: &str, input: &str) -> Result<Value> {
+    match def.kind {
+        Kind::Str => Ok(Value::String(input.to_string())),
+        Kind::Bool => match input.to_ascii_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => Ok(Value::Boolean(true)),
+            "false" | "no" | "off" | "0" => Ok(Value::Boolean(false)),
+            _ => bail!("Invalid value '{}' for {} (expected true or false)", input, key),
+        },
+        Kind::Int => input.parse::<u32>()
+            .map(|n| Value::Integer(n.into()))
+            .map_err(|_| anyhow::anyhow!("Invalid value '{}' for {} (expected a whole number)", input, key)),
+        Kind::Float => input.parse::<f64>().ok()
+            .filter(|n| n.is_finite() && *n >= 0.0)
+            .map(Value::Float)
+            .ok_or_else(|| anyhow::anyhow!("Invalid value '{}' for {} (expected a number)", input, key)),
+        Kind::Choice(choices) => {
+            if choices.contains(&input) {
+                Ok(Value::String(input.to_string()))
+            } else {
+                bail!("Invalid value '{}' for {} (expected one of: {})", input, key, choices.join(", "))
+            }
+        }
+        Kind::List => Ok(Value::Array(
+            input.split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| Value::String(item.to_string()))
+                .collect(),
+        )),
+    }
+}
+
+/// Display form of a value (strings without quotes, lists comma-separated)
+pub fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items.iter().map(display_value).collect::<Vec<_>>().join(", "),
+        other => other.to_string(),
+    }
+}
+
+/// Get a dotted key from a table
+pub fn get_path<'a>(table: &'a Table, key: &str) -> Option<&'a Value> {
+    let mut parts = key.split('.');
+    let mut value = table.get(parts.next()?)?;
+    for part in parts {
+        value = value.as_table()?.get(part)?;
+    }
+    Some(value)
+}
+
+/// Set a dotted key in a table, creating intermediate tables
+pub fn set_path(table: &mut Table, key: &str, value: Value) -> Result<()> {
+    let (parents, last) = match key.rsplit_once('.') {
+        Some((parents, last)) => (Some(parents), last),
+        None => (None, key),
+    };
+
+    let mut current = table;
+    for part in parents.into_iter().flat_map(|p| p.split('.')) {
+        current = current.entry(part.to_string())
+            .or_insert_with(|| Value::Table(Table::new()))
+            .as_table_mut()
+            .with_context(|| format!("'{}' is not a table", part))?;
+    }
+    current.insert(last.to_string(), value);
+    Ok(())
+}
+
+/// Remove a dotted key from a table, dropping tables left empty. Returns the removed value.
+pub fn remove_path(table: &mut Table, key: &str) -> Option<Value> {
+    match key.split_once('.') {
+        None => table.remove(key),
+        Some((first, rest)) => {
+            let child = table.get_mut(first)?.as_table_mut()?;
+            let removed = remove_path(child, rest);
+            if child.is_empty() {
+                table.remove(first);
+            }
+            removed
+        }
+    }
+}
+
+/// Recursively merge `upper` over `lower`
+fn merge(lower: &mut Table, upper: &Table) {
+    for (key, value) in upper {
+        match (lower.get_mut(key), value) {
+            (Some(Value::Table(lower_table)), Value::Table(upper_table)) => {
+                merge(lower_table, upper_table);
+            }
+            _ => {
+                lower.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// Read a TOML file as a raw table (empty if the file does not exist)
+pub fn read_table(path: &Path) -> Result<Table> {
+    if !path.exists() {
+        return Ok(Table::new());
+    }
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    content.parse::<Table>()
+        .with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Write a raw table as TOML, creating the parent directory
+pub fn write_table(path: &Path, table: &Table) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .context("Failed to create config directory")?;
+    }
+    let content = toml::to_string_pretty(table)
+        .context("Failed to serialize config")?;
+    std::fs::write(path, content)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Create `path` if needed and make it readable and writable by its owner only.
+/// Called before secrets are written, so they never sit in a world-readable file.
+pub fn restrict_permissions(path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .context("Failed to create config directory")?;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .mode(0o600)
+            .open(path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("Failed to set permissions on {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Path of the per-vault settings file
+pub fn vault_config_path(vault_dir: &Path) -> PathBuf {
+    vault_dir.join(VAULT_CONFIG_FILE)
+}
+
+/// Path of the shared settings file inside the vault repo
+pub fn shared_config_path(vault_dir: &Path) -> PathBuf {
+    vault_dir.join("repo").join(SHARED_CONFIG_FILE)
+}
+
+/// Shareable keys from the vault's `.gfv.toml`; everything else in it is dropped.
+/// A broken file only costs the shared settings, so it can still be fixed with gfv.
+fn read_shared(vault_dir: &Path) -> Result<Table> {
+    let table = match read_table(&shared_config_path(vault_dir)) {
+        Ok(table) => table,
+        Err(e) => {
+            // Settings are loaded several times per command; warn once per file
+            static WARNED: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+            let mut warned = WARNED.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            if !warned.iter().any(|path| path == vault_dir) {
+                warned.push(vault_dir.to_path_buf());
+                eprintln!("{} {:#}", "Warning:".yellow().bold(), e);
+                eprintln!("  Shared vault settings are ignored until the file is fixed");
+            }
+            return Ok(Table::new());
+        }
+    };
+
+    select_keys(&table, |def| def.shared)
+}
+
+/// The keys of `table` whose definition passes `keep`; anything else is dropped
+fn select_keys(table: &Table, keep: impl Fn(&KeyDef) -> bool) -> Result<Table> {
+    let mut selected = Table::new();
+    for def in KEYS.iter().filter(|def| keep(def)) {
+        if let Some(value) = get_path(table, def.key) {
+            set_path(&mut selected, def.key, value.clone())?;
+        }
+    }
+    Ok(selected)
+}
+
+/// All configuration layers, lowest precedence first
+pub struct Layers {
+    layers: Vec<(Origin, Table)>,
+}
+
+impl Layers {
+    /// Load defaults, the global config, the vault's shared and local settings
+    /// (if a vault is given) and env overrides
+    pub fn load(vault_dir: Option<&Path>) -> Result<Self> {
+        let mut layers = Vec::new();
+
+        let defaults = Value::try_from(Config::default())
+            .context("Failed to build default config")?;
+        if let Value::Table(defaults) = defaults {
+            layers.push((Origin::Default, defaults));
+        }
+
+        layers.push((Origin::Global, read_table(&Config::config_path()?)?));
+
+        // Only secrets are read from the credentials file
+        let credentials = read_table(&Config::credentials_path()?)?;
+        layers.push((Origin::Credentials, select_keys(&credentials, |def| def.secret)?));
+
+        if let Some(vault_dir) = vault_dir {
+            layers.push((Origin::Shared, read_shared(vault_dir)?));
+            // Vaults, aliases and credentials are global, as `config --local` enforces
+            let local = read_table(&vault_config_path(vault_dir))?;
+            layers.push((Origin::Vault, select_keys(&local, |def| !def.global_only)?));
+        }
+
+        for def in KEYS.iter().filter(|def| !def.global_only) {
+            let var = env_var(def.key);
+            let Some(raw) = std::env::var_os(&var).filter(|v| !v.is_empty()) else {
+                continue;
+            };
+            let raw = raw.into_string()
+                .map_err(|_| anyhow::anyhow!("${} is not valid UTF-8", var))?;
+            let value = parse_value(def, def.key, &raw)
+                .with_context(|| format!("Invalid ${}", var))?;
+            let mut table = Table::new();
+            set_path(&mut table, def.key, value)?;
+            layers.push((Origin::Env(var), table));
+        }
+
+        Ok(Self { layers })
+    }
+
+    /// Effective value of a key and the layer it came from
+    pub fn resolve(&self, key: &str) -> Option<(&Value, &Origin)> {
+        self.layers.iter().rev()
+            .find_map(|(origin, table)| get_path(table, key).map(|value| (value, origin)))
+    }
+
+    /// Highest layer above `origin` that sets `key`, if any
+    pub fn overridden_above(&self, key: &str, origin: &Origin) -> Option<&Origin> {
+        let position = self.layers.iter().position(|(o, _)| o == origin)?;
+        self.layers[position + 1..].iter().rev()
+            .find(|(_, table)| get_path(table, key).is_some())
+            .map(|(o, _)| o)
+    }
+
+    /// Alias keys set in any layer, sorted
+    pub fn alias_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.layers.iter()
+            .filter_map(|(_, table)| table.get("aliases").and_then(Value::as_table))
+            .flat_map(|aliases| aliases.keys().map(|name| format!("{}{}", ALIAS_PREFIX, name)))
+            .collect();
+        keys.sort();
+        keys.dedup();
+        keys
+    }
+
+    /// Merge all layers into an effective `Config`
+    pub fn merged(&self) -> Result<Config> {
+        let mut table = Table::new();
+        for (_, layer) in &self.layers {
+            merge(&mut table, layer);
+        }
+        Value::Table(table).try_into()
+            .context("Invalid configuration value")
+    }
+}
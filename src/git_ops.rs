@@ -1,56 +1,61 @@
 // Git operations module - wraps git2 operations
 
 use anyhow::{bail, Context, Result};
-use git2::{Repository, Signature, IndexAddOption, Cred, RemoteCallbacks};
+use git2::{Delta, Repository, Signature, IndexAddOption, RemoteCallbacks};
+use std::collections::BTreeMap;
 use std::path::Path;
 use crate::error::VaultError;
 
+pub mod auth;
+pub mod bundle;
+pub mod history;
+pub mod repair;
+
+pub use auth::AuthOptions;
+
+/// Patch text and stats for one changed file
+pub struct FileDiff {
+    /// Path relative to the repo root
+    pub path: String,
+    /// Previous path for renames and copies
+    pub old_path: Option<String>,
+    /// A(dded), M(odified), D(eleted), R(enamed), C(opied) or T(ype change)
+    pub status: char,
+    pub added: usize,
+    pub removed: usize,
+    pub binary: bool,
+    pub patch: String,
+}
+
+/// Contents of a vault entry read from a commit tree
+pub enum EntryContent {
+    File(Vec<u8>),
+    /// Files keyed by their path relative to the entry directory
+    Directory(BTreeMap<String, Vec<u8>>),
+}
+
 pub struct GitRepo {
     repo: Repository,
+    auth: AuthOptions,
 }
 
 impl GitRepo {
     /// Create callbacks for Git authentication
-    /// Supports both SSH keys and Git credential manager (for HTTPS)
-    fn create_auth_callbacks<'a>() -> RemoteCallbacks<'a> {
-        let mut callbacks = RemoteCallbacks::new();
-
-        callbacks.credentials(|url, username_from_url, allowed_types| {
-            // Try different credential methods based on URL type and allowed types
-
-            // 1. Try credential helper first (works for HTTPS with credential manager)
-            if allowed_types.contains(git2::CredentialType::USERNAME | git2::CredentialType::USER_PASS_PLAINTEXT)
-                || allowed_types.contains(git2::CredentialType::USER_PASS_PLAINTEXT) {
-                if let Ok(cred) = Cred::credential_helper(&git2::Config::open_default().unwrap(), url, username_from_url) {
-                    return Ok(cred);
-                }
-            }
-
-            // 2. Try SSH agent for SSH URLs
-            if allowed_types.contains(git2::CredentialType::SSH_KEY) {
-                if let Ok(cred) = Cred::ssh_key_from_agent(username_from_url.unwrap_or("git")) {
-                    return Ok(cred);
-                }
-            }
-
-            // 3. Try default SSH key
-            if allowed_types.contains(git2::CredentialType::SSH_KEY) {
-                if let Ok(cred) = Cred::ssh_key(
-                    username_from_url.unwrap_or("git"),
-                    None,
-                    std::path::Path::new(&format!("{}/.ssh/id_rsa",
-                        dirs::home_dir().unwrap().display())),
-                    None,
-                ) {
-                    return Ok(cred);
-                }
-            }
+    /// Supports SSH keys (agent, explicit, ~/.ssh/config and default keys),
+    /// HTTPS tokens and the Git credential manager
+    fn create_auth_callbacks<'a>(&self, remote: &git2::Remote) -> RemoteCallbacks<'a> {
+        auth::create_callbacks(&self.auth, remote.url().unwrap_or_default())
+    }
 
-            // If all else fails, return an error
-            Err(git2::Error::from_str("No authentication method available"))
-        });
+    /// Use the given authentication settings for remote operations
+    pub fn with_auth(mut self, auth: AuthOptions) -> Self {
+        self.auth = auth;
+        self
+    }
 
-        callbacks
+    /// Switch authentication settings, e.g. before talking to another remote
+    pub fn set_auth(&mut self, auth: AuthOptions) {
+        self.auth = auth;
     }
 
     /// Initialize a new Git repository
@@ -58,7 +63,7 @@ impl GitRepo {
         let repo = Repository::init(path)
             .context("Failed to initialize Git repository")?;
 
-        Ok(Self { repo })
+        Ok(Self { repo, auth: AuthOptions::default() })
     }
 
     /// Open an existing Git repository
@@ -66,14 +71,14 @@ impl GitRepo {
         let repo = Repository::open(path)
             .context("Failed to open Git repository")?;
 
-        Ok(Self { repo })
+        Ok(Self { repo, auth: AuthOptions::default() })
     }
 
     /// Clone a remote repository
-    pub fn clone(url: &str, path: &Path) -> Result<Self> {
+    pub fn clone(url: &str, path: &Path, auth: AuthOptions) -> Result<Self> {
         // Set up authentication callbacks
         let mut fetch_options = git2::FetchOptions::new();
-        fetch_options.remote_callbacks(Self::create_auth_callbacks());
+        fetch_options.remote_callbacks(auth::create_callbacks(&auth, url));
 
         let mut builder = git2::build::RepoBuilder::new();
         builder.fetch_options(fetch_options);
@@ -81,7 +86,7 @@ impl GitRepo {
         let repo = builder.clone(url, path)
             .context("Failed to clone repository")?;
 
-        Ok(Self { repo })
+        Ok(Self { repo, auth })
     }
 
     /// Check if the repository is empty (no commits)
@@ -96,15 +101,6 @@ impl GitRepo {
         Ok(())
     }
 
-    /// Get the URL of a remote
-    pub fn get_remote_url(&self, name: &str) -> Result<Option<String>> {
-        let remote = self.repo.find_remote(name);
-        match remote {
-            Ok(remote) => Ok(remote.url().map(|s| s.to_string())),
-            Err(_) => Ok(None),
-        }
-    }
-
     /// Set or update a remote URL
     pub fn set_remote(&self, name: &str, url: &str) -> Result<()> {
         // Check if remote exists
@@ -120,6 +116,15 @@ impl GitRepo {
         Ok(())
     }
 
+    /// Remove a remote if it exists
+    pub fn remove_remote(&self, name: &str) -> Result<()> {
+        if self.repo.find_remote(name).is_ok() {
+            self.repo.remote_delete(name)
+                .context("Failed to remove remote")?;
+        }
+        Ok(())
+    }
+
     /// Check if repository has uncommitted changes
     pub fn has_changes(&self) -> Result<bool> {
         let statuses = self.repo.statuses(None)
@@ -128,52 +133,83 @@ impl GitRepo {
         Ok(!statuses.is_empty())
     }
 
-    /// Get the diff of all changes (staged and unstaged)
-    pub fn get_diff(&self) -> Result<String> {
-        let mut diff_output = String::new();
-
-        // Get diff for unstaged changes
-        let diff = self.repo.diff_index_to_workdir(None, None)?;
-        diff_output.push_str(&Self::format_diff(&diff)?);
-
-        // Get diff for staged changes
-        let head = self.repo.head()?;
-        let tree = head.peel_to_tree()?;
-        let diff_staged = self.repo.diff_tree_to_index(Some(&tree), None, None)?;
-        diff_output.push_str(&Self::format_diff(&diff_staged)?);
-
-        Ok(diff_output)
+    /// Check if anything is staged for the next commit
+    pub fn has_staged_changes(&self) -> Result<bool> {
+        Ok(self.staged_diff()?.deltas().len() > 0)
     }
 
-    /// Format a diff object as a string
-    fn format_diff(diff: &git2::Diff) -> Result<String> {
-        let mut output = String::new();
+    /// Diff of the index against HEAD (everything, before the first commit)
+    fn staged_diff(&self) -> Result<git2::Diff<'_>> {
+        let tree = self.repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+        self.repo.diff_tree_to_index(tree.as_ref(), None, None)
+            .context("Failed to diff the index")
+    }
 
-        diff.print(git2::DiffFormat::Patch, |_delta, _hunk, line| {
-            let origin = line.origin();
-            let content = std::str::from_utf8(line.content()).unwrap_or("");
+    /// Whether a file, or a directory with files in it, is in the index
+    pub fn index_has_path(&self, path: &str) -> bool {
+        let Ok(index) = self.repo.index() else {
+            return false;
+        };
+        let dir = format!("{}/", path);
+        index.iter().any(|entry| {
+            let entry_path = String::from_utf8_lossy(&entry.path);
+            entry_path == path || entry_path.starts_with(&dir)
+        })
+    }
 
-            match origin {
-                '+' | '-' | ' ' => {
-                    output.push(origin);
-                    output.push_str(content);
-                }
-                'F' => {
-                    output.push_str("diff --git ");
-                    output.push_str(content);
-                }
-                'H' => {
-                    output.push_str("@@ ");
-                    output.push_str(content);
-                }
-                _ => {
-                    output.push_str(content);
-                }
+    /// Get the diff of the staged changes, split per file
+    pub fn get_staged_diffs(&self) -> Result<Vec<FileDiff>> {
+        let mut staged = self.staged_diff()?;
+
+        // Detect renames so they show up as one change
+        let mut find = git2::DiffFindOptions::new();
+        find.renames(true);
+        staged.find_similar(Some(&mut find))?;
+
+        let mut files = Vec::new();
+        for idx in 0..staged.deltas().len() {
+            let delta = staged.get_delta(idx).context("Missing diff delta")?;
+            let to_string = |p: &Path| p.to_string_lossy().replace('\\', "/");
+            let path = delta.new_file().path()
+                .or_else(|| delta.old_file().path())
+                .map(to_string)
+                .unwrap_or_default();
+            let status = match delta.status() {
+                Delta::Added | Delta::Untracked => 'A',
+                Delta::Deleted => 'D',
+                Delta::Renamed => 'R',
+                Delta::Copied => 'C',
+                Delta::Typechange => 'T',
+                _ => 'M',
+            };
+            let old_path = match status {
+                'R' | 'C' => delta.old_file().path().map(to_string),
+                _ => None,
+            };
+
+            let mut file = FileDiff {
+                path,
+                old_path,
+                status,
+                added: 0,
+                removed: 0,
+                binary: delta.flags().is_binary(),
+                patch: String::new(),
+            };
+
+            if let Some(mut patch) = git2::Patch::from_diff(&staged, idx)? {
+                let (_, added, removed) = patch.line_stats()?;
+                file.added = added;
+                file.removed = removed;
+                file.binary |= patch.delta().flags().is_binary();
+                let buf = patch.to_buf()?;
+                file.patch = String::from_utf8_lossy(&buf).into_owned();
             }
-            true
-        })?;
 
-        Ok(output)
+            files.push(file);
+        }
+
+        Ok(files)
     }
 
     /// Check if a remote branch exists
@@ -235,6 +271,120 @@ impl GitRepo {
         }
     }
 
+    /// Count commits the local branch is ahead of and behind its remote-tracking branch.
+    /// Returns None when the remote branch is unknown (never fetched or pushed).
+    pub fn ahead_behind(&self, remote_name: &str, branch: &str) -> Result<Option<(usize, usize)>> {
+        let Some((local_oid, remote_oid)) = self.branch_tips(remote_name, branch)? else {
+            return Ok(None);
+        };
+
+        // Nothing committed locally yet: every remote commit is behind
+        let Some(local_oid) = local_oid else {
+            let mut revwalk = self.repo.revwalk()?;
+            revwalk.push(remote_oid)?;
+            return Ok(Some((0, revwalk.count())));
+        };
+
+        let (ahead, behind) = self.repo.graph_ahead_behind(local_oid, remote_oid)
+            .context("Failed to compare local and remote branches")?;
+
+        Ok(Some((ahead, behind)))
+    }
+
+    /// Paths changed on each side since the local and remote branches diverged,
+    /// as (local, upstream). Both are empty when the remote branch is unknown.
+    pub fn divergent_paths(&self, remote_name: &str, branch: &str) -> Result<(Vec<String>, Vec<String>)> {
+        let Some((local_oid, remote_oid)) = self.branch_tips(remote_name, branch)? else {
+            return Ok((Vec::new(), Vec::new()));
+        };
+
+        let remote_tree = self.repo.find_commit(remote_oid)?.tree()?;
+        // Nothing committed locally yet: everything upstream is a change
+        let Some(local_oid) = local_oid else {
+            return Ok((Vec::new(), self.changed_paths(None, &remote_tree)?));
+        };
+
+        let base_tree = match self.repo.merge_base(local_oid, remote_oid) {
+            Ok(base) => Some(self.repo.find_commit(base)?.tree()?),
+            // Unrelated histories: everything on each side is a change
+            Err(_) => None,
+        };
+        let local_tree = self.repo.find_commit(local_oid)?.tree()?;
+
+        let local = self.changed_paths(base_tree.as_ref(), &local_tree)?;
+        let upstream = self.changed_paths(base_tree.as_ref(), &remote_tree)?;
+
+        Ok((local, upstream))
+    }
+
+    /// Resolve the local and remote-tracking branch tips. The local tip is None
+    /// while the branch is unborn (nothing committed yet).
+    fn branch_tips(&self, remote_name: &str, branch: &str) -> Result<Option<(Option<git2::Oid>, git2::Oid)>> {
+        let local_refname = format!("refs/heads/{}", branch);
+        let remote_refname = format!("refs/remotes/{}/{}", remote_name, branch);
+
+        let local_oid = self.repo.refname_to_id(&local_refname).ok();
+
+        match self.repo.refname_to_id(&remote_refname) {
+            Ok(remote_oid) => Ok(Some((local_oid, remote_oid))),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// List paths that differ between two trees (old and new paths for renames)
+    fn changed_paths(&self, old: Option<&git2::Tree>, new: &git2::Tree) -> Result<Vec<String>> {
+        let diff = self.repo.diff_tree_to_tree(old, Some(new), None)
+            .context("Failed to diff trees")?;
+
+        let mut paths = Vec::new();
+        for delta in diff.deltas() {
+            for file in [delta.old_file(), delta.new_file()] {
+                if let Some(path) = file.path().and_then(|p| p.to_str()) {
+                    if !paths.iter().any(|p| p == path) {
+                        paths.push(path.to_string());
+                    }
+                }
+            }
+        }
+
+        Ok(paths)
+    }
+
+    /// Read a file or directory from the remote-tracking branch without
+    /// touching the working tree. Returns None if the path does not exist there.
+    pub fn read_remote_entry(&self, remote_name: &str, branch: &str, path: &str) -> Result<Option<EntryContent>> {
+        let refname = format!("refs/remotes/{}/{}", remote_name, branch);
+        let tree = self.repo.find_reference(&refname)
+            .context("Failed to find remote branch")?
+            .peel_to_tree()
+            .context("Failed to read remote tree")?;
+
+        let entry = match tree.get_path(Path::new(path)) {
+            Ok(entry) => entry,
+            Err(_) => return Ok(None),
+        };
+        let object = entry.to_object(&self.repo)?;
+
+        if let Some(blob) = object.as_blob() {
+            return Ok(Some(EntryContent::File(blob.content().to_vec())));
+        }
+
+        let Some(subtree) = object.as_tree() else {
+            return Ok(None);
+        };
+
+        let mut files = BTreeMap::new();
+        subtree.walk(git2::TreeWalkMode::PreOrder, |dir, item| {
+            if let Ok(blob) = item.to_object(&self.repo).and_then(|o| o.peel_to_blob()) {
+                let name = item.name().unwrap_or_default();
+                files.insert(format!("{}{}", dir, name), blob.content().to_vec());
+            }
+            git2::TreeWalkResult::Ok
+        })?;
+
+        Ok(Some(EntryContent::Directory(files)))
+    }
+
     /// Add all changes to staging
     pub fn add_all(&self) -> Result<()> {
         let mut index = self.repo.index()
@@ -242,6 +392,9 @@ impl GitRepo {
 
         index.add_all(["*"].iter(), IndexAddOption::DEFAULT, None)
             .context("Failed to add files to index")?;
+        // Also stage deletions
+        index.update_all(["*"].iter(), None)
+            .context("Failed to update index")?;
 
         index.write()
             .context("Failed to write index")?;
@@ -249,8 +402,45 @@ impl GitRepo {
         Ok(())
     }
 
-    /// Commit staged changes
-    pub fn commit(&self, message: &str) -> Result<()> {
+    /// Stage everything under the given paths (relative to the repo root), including deletions
+    pub fn stage_paths(&self, paths: &[&str]) -> Result<()> {
+        let mut index = self.repo.index()
+            .context("Failed to get repository index")?;
+
+        index.add_all(paths.iter(), IndexAddOption::DEFAULT, None)
+            .context("Failed to add files to index")?;
+        index.update_all(paths.iter(), None)
+            .context("Failed to update index")?;
+
+        index.write()
+            .context("Failed to write index")?;
+
+        Ok(())
+    }
+
+    /// Stage a single path (relative to the repo root), including its deletion
+    pub fn stage_path(&self, path: &Path) -> Result<()> {
+        let mut index = self.repo.index()
+            .context("Failed to get repository index")?;
+
+        let workdir = self.repo.workdir()
+            .context("Repository has no working directory")?;
+        if workdir.join(path).exists() {
+            index.add_path(path)
+                .with_context(|| format!("Failed to stage {}", path.display()))?;
+        } else {
+            index.remove_path(path)
+                .with_context(|| format!("Failed to stage removal of {}", path.display()))?;
+        }
+
+        index.write()
+            .context("Failed to write index")?;
+
+        Ok(())
+    }
+
+    /// Commit staged changes, returning the new commit id
+    pub fn commit(&self, message: &str) -> Result<git2::Oid> {
         let mut index = self.repo.index()
             .context("Failed to get repository index")?;
 
@@ -263,7 +453,7 @@ impl GitRepo {
         let signature = Signature::now("gfv", "gfv@local")
             .context("Failed to create signature")?;
 
-        if self.is_empty()? {
+        let commit_id = if self.is_empty()? {
             // Initial commit - no parent
             self.repo.commit(
                 Some("HEAD"),
@@ -272,7 +462,7 @@ impl GitRepo {
                 message,
                 &tree,
                 &[],
-            ).context("Failed to create initial commit")?;
+            ).context("Failed to create initial commit")?
         } else {
             // Normal commit with parent
             let parent_commit = self.repo.head()?.peel_to_commit()?;
@@ -283,10 +473,10 @@ impl GitRepo {
                 message,
                 &tree,
                 &[&parent_commit],
-            ).context("Failed to create commit")?;
-        }
+            ).context("Failed to create commit")?
+        };
 
-        Ok(())
+        Ok(commit_id)
     }
 
     /// Fetch from remote (without merging)
@@ -296,7 +486,7 @@ impl GitRepo {
 
         // Set up authentication callbacks
         let mut fetch_options = git2::FetchOptions::new();
-        fetch_options.remote_callbacks(Self::create_auth_callbacks());
+        fetch_options.remote_callbacks(self.create_auth_callbacks(&remote));
 
         // Fetch from remote
         let refspec = format!("refs/heads/{}", branch);
@@ -313,16 +503,22 @@ impl GitRepo {
 
         // Set up authentication callbacks
         let mut fetch_options = git2::FetchOptions::new();
-        fetch_options.remote_callbacks(Self::create_auth_callbacks());
+        fetch_options.remote_callbacks(self.create_auth_callbacks(&remote));
 
         // Fetch from remote
         let refspec = format!("refs/heads/{}", branch);
         remote.fetch(&[&refspec], Some(&mut fetch_options), None)
             .context("Failed to fetch from remote")?;
 
-        // Get the remote branch reference
         let fetch_refname = format!("refs/remotes/{}/{}", remote_name, branch);
-        let fetch_ref = self.repo.find_reference(&fetch_refname)
+        self.merge_fetched(&fetch_refname, branch, use_rebase)
+    }
+
+    /// Bring a fetched reference (e.g. `refs/remotes/origin/main`) into the local
+    /// branch: fast-forward, or rebase local commits onto it
+    pub fn merge_fetched(&self, fetch_refname: &str, branch: &str, use_rebase: bool) -> Result<()> {
+        // Get the fetched branch reference
+        let fetch_ref = self.repo.find_reference(fetch_refname)
             .context("Failed to find remote branch after fetch")?;
 
         let fetch_commit = self.repo.reference_to_annotated_commit(&fetch_ref)
@@ -380,17 +576,43 @@ impl GitRepo {
 
     /// Push changes to remote
     pub fn push(&self, remote_name: &str, branch: &str) -> Result<()> {
+        self.push_branch(remote_name, branch, branch, false)
+    }
+
+    /// Push a local branch to a (possibly differently named) remote branch
+    pub fn push_branch(&self, remote_name: &str, local_branch: &str, remote_branch: &str, force: bool) -> Result<()> {
         let mut remote = self.repo.find_remote(remote_name)
             .context("Failed to find remote")?;
 
-        let refspec = format!("refs/heads/{}:refs/heads/{}", branch, branch);
+        let refspec = format!(
+            "{}refs/heads/{}:refs/heads/{}",
+            if force { "+" } else { "" },
+            local_branch,
+            remote_branch
+        );
+
+        // The server reports rejected refs (e.g. non-fast-forward) through a
+        // callback rather than as an error from push()
+        let rejected = std::cell::RefCell::new(None);
 
         // Set up authentication callbacks
+        let mut callbacks = self.create_auth_callbacks(&remote);
+        callbacks.push_update_reference(|refname, status| {
+            if let Some(message) = status {
+                *rejected.borrow_mut() = Some(format!("{}: {}", refname, message));
+            }
+            Ok(())
+        });
         let mut push_options = git2::PushOptions::new();
-        push_options.remote_callbacks(Self::create_auth_callbacks());
+        push_options.remote_callbacks(callbacks);
 
         remote.push(&[&refspec], Some(&mut push_options))
             .context("Failed to push to remote")?;
+        drop(push_options);
+
+        if let Some(reason) = rejected.into_inner() {
+            bail!("Push rejected by remote ({})", reason);
+        }
 
         Ok(())
     }
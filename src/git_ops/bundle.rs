@@ -0,0 +1,175 @@
+// Git bundles - sync through files for machines without access to a remote.
+// libgit2 cannot read or write bundles, so this uses the git command line.
+
+use anyhow::{bail, Context, Result};
+use git2::{build::CheckoutBuilder, Oid};
+use std::path::Path;
+use std::process::Command;
+
+use super::GitRepo;
+
+/// Ref carrying the shared file entries inside a bundle
+const MANIFEST_REF: &str = "refs/gfv/manifest";
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Where the branch of an applied bundle is fetched to
+pub const BUNDLE_REMOTE: &str = "bundle";
+
+/// A bundle that was written
+pub struct CreatedBundle {
+    /// Branch tip in the bundle; pass it to `--since` for the next bundle
+    pub tip: Oid,
+    pub commits: usize,
+}
+
+/// A bundle that was fetched
+pub struct FetchedBundle {
+    /// Branch name in the bundle
+    pub branch: String,
+    /// Fetched branch, `refs/remotes/bundle/<branch>`
+    pub refname: String,
+    /// Shared file entries, if the bundle has them
+    pub manifest: Option<Vec<u8>>,
+}
+
+impl GitRepo {
+    /// Write `branch` and `manifest` to a bundle file. With `since`, only commits
+    /// after that ref are included; the receiving vault must already have it.
+    pub fn create_bundle(&self, file: &Path, branch: &str, since: Option<&str>, manifest: &[u8]) -> Result<CreatedBundle> {
+        let branch_ref = format!("refs/heads/{}", branch);
+        let tip = self.repo.refname_to_id(&branch_ref)
+            .with_context(|| format!("Branch '{}' has no commits", branch))?;
+
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.push(tip)?;
+        let mut range = branch_ref.clone();
+        if let Some(since) = since {
+            let base = self.repo.revparse_single(since)
+                .and_then(|object| object.peel_to_commit())
+                .with_context(|| format!("Unknown commit or ref '{}'", since))?;
+            revwalk.hide(base.id())?;
+            range = format!("{}..{}", base.id(), branch_ref);
+        }
+        let commits = revwalk.count();
+        if commits == 0 {
+            bail!("No commits on {} since {}", branch, since.unwrap_or_default());
+        }
+
+        // The manifest travels as a one-file commit on its own ref
+        let blob = self.repo.blob(manifest)?;
+        let mut tree = self.repo.treebuilder(None)?;
+        tree.insert(MANIFEST_FILE, blob, 0o100644)?;
+        let tree = self.repo.find_tree(tree.write()?)?;
+        let signature = git2::Signature::now("gfv", "gfv@local")?;
+        let manifest_commit = self.repo.commit(None, &signature, &signature, "gfv manifest", &tree, &[])?;
+        self.repo.reference(MANIFEST_REF, manifest_commit, true, "gfv bundle create")?;
+
+        let file = file.to_string_lossy();
+        let result = self.git(&["bundle", "create", &file, &range, MANIFEST_REF]);
+        self.repo.find_reference(MANIFEST_REF)?.delete()?;
+        result.context("Failed to create bundle")?;
+
+        Ok(CreatedBundle { tip, commits })
+    }
+
+    /// Check a bundle against this repository and fetch its branch to
+    /// `refs/remotes/bundle/<branch>`, without merging
+    pub fn fetch_bundle(&self, file: &Path) -> Result<FetchedBundle> {
+        let file = file.to_string_lossy();
+
+        // Fails if the bundle builds on commits this repository doesn't have
+        self.git(&["bundle", "verify", &file])
+            .context("Bundle cannot be applied to this vault")?;
+
+        let heads = self.git(&["bundle", "list-heads", &file])
+            .context("Failed to read bundle")?;
+        let refs: Vec<&str> = heads.lines()
+            .filter_map(|line| line.split_whitespace().nth(1))
+            .collect();
+        let Some(branch) = refs.iter().find_map(|name| name.strip_prefix("refs/heads/")) else {
+            bail!("Bundle has no branch");
+        };
+        let has_manifest = refs.contains(&MANIFEST_REF);
+
+        let refname = format!("refs/remotes/{}/{}", BUNDLE_REMOTE, branch);
+        let mut refspecs = vec![format!("+refs/heads/{}:{}", branch, refname)];
+        if has_manifest {
+            refspecs.push(format!("+{0}:{0}", MANIFEST_REF));
+        }
+        let mut args = vec!["fetch", "--quiet", "--no-tags", &file];
+        args.extend(refspecs.iter().map(String::as_str));
+        self.git(&args).context("Failed to fetch from bundle")?;
+
+        let manifest = if has_manifest {
+            let mut reference = self.repo.find_reference(MANIFEST_REF)?;
+            let content = reference.peel_to_tree()?
+                .get_name(MANIFEST_FILE)
+                .map(|entry| self.repo.find_blob(entry.id()).map(|blob| blob.content().to_vec()))
+                .transpose()?;
+            reference.delete()?;
+            content
+        } else {
+            None
+        };
+
+        Ok(FetchedBundle { branch: branch.to_string(), refname, manifest })
+    }
+
+    /// Whether the current branch and `refname` have a common ancestor
+    pub fn shares_history(&self, refname: &str) -> Result<bool> {
+        let Ok(head) = self.repo.head() else {
+            return Ok(false);
+        };
+        let head = head.peel_to_commit()?.id();
+        let other = self.repo.refname_to_id(refname)?;
+        Ok(self.repo.merge_base(head, other).is_ok())
+    }
+
+    /// Whether nothing was committed yet apart from what vault creation commits
+    /// (a root commit holding only `.gitignore`)
+    pub fn is_fresh(&self) -> Result<bool> {
+        let Ok(head) = self.repo.head() else {
+            return Ok(true);
+        };
+        let commit = head.peel_to_commit()?;
+        let tree = commit.tree()?;
+        Ok(commit.parent_count() == 0
+            && tree.iter().all(|entry| entry.name() == Some(".gitignore")))
+    }
+
+    /// Point the current branch at `refname` and check it out, discarding local
+    /// history; also works before the first commit
+    pub fn reset_to(&self, refname: &str) -> Result<()> {
+        let target = self.repo.find_reference(refname)?.peel_to_commit()?;
+        let branch = self.repo.find_reference("HEAD")?
+            .symbolic_target()
+            .context("HEAD is detached")?
+            .to_string();
+        self.repo.reference(&branch, target.id(), true, "gfv bundle apply")
+            .context("Failed to update branch")?;
+        self.repo.checkout_head(Some(CheckoutBuilder::new().force()))
+            .context("Failed to check out files")?;
+        Ok(())
+    }
+
+    /// Run the git command line in this repository and return its output
+    fn git(&self, args: &[&str]) -> Result<String> {
+        let workdir = self.repo.workdir()
+            .context("Repository has no working directory")?;
+        let output = Command::new("git")
+            .arg("-C").arg(workdir)
+            .args(args)
+            .output()
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => anyhow::anyhow!("git is not installed (needed for bundles)"),
+                _ => anyhow::Error::new(e).context("Failed to run git"),
+            })?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            bail!("{}", if stderr.trim().is_empty() { stdout.trim() } else { stderr.trim() });
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
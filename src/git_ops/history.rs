@@ -0,0 +1,174 @@
+// History import - replays commits of another repository with paths mapped into the vault
+
+use anyhow::{Context, Result};
+use git2::{build::CheckoutBuilder, Index, IndexEntry, IndexTime, Oid, Repository, Sort};
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::GitRepo;
+
+/// File modes that can be stored in the vault (regular and executable files)
+const FILE_MODES: [i32; 2] = [0o100644, 0o100755];
+
+/// Rewritten history of another repository
+pub struct ImportedHistory {
+    /// Last rewritten commit, not yet on any branch
+    pub tip: Oid,
+    /// Number of commits created
+    pub commits: usize,
+}
+
+impl GitRepo {
+    /// Replay the history of `source` (HEAD and its ancestors) into this repository.
+    /// `map` turns a path in `source` into a vault path, or drops it with `None`.
+    /// Author, committer and message are kept; commits that change nothing
+    /// after mapping are left out. Returns `None` if `source` has no commits.
+    pub fn import_history(&self, source: &Path, map: &dyn Fn(&str) -> Option<String>) -> Result<Option<ImportedHistory>> {
+        let source = Repository::open(source)
+            .with_context(|| format!("Failed to open repository {}", source.display()))?;
+        let Ok(head) = source.head() else {
+            return Ok(None);
+        };
+        let head = head.peel_to_commit().context("Failed to read HEAD commit")?;
+
+        let mut revwalk = source.revwalk()?;
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+        revwalk.push(head.id())?;
+
+        // Source commit -> rewritten commit (or the commit it collapsed into)
+        let mut rewritten: HashMap<Oid, Oid> = HashMap::new();
+        let mut commits = 0;
+
+        for oid in revwalk {
+            let commit = source.find_commit(oid?)?;
+            let tree_id = self.mapped_tree(&source, &commit.tree()?, map)?;
+
+            let mut parents = Vec::new();
+            for parent in commit.parent_ids() {
+                if let Some(&new_parent) = rewritten.get(&parent) {
+                    if !parents.contains(&new_parent) {
+                        parents.push(new_parent);
+                    }
+                }
+            }
+
+            // Nothing left of this commit after mapping
+            if let [parent] = parents.as_slice() {
+                if self.repo.find_commit(*parent)?.tree_id() == tree_id {
+                    rewritten.insert(commit.id(), *parent);
+                    continue;
+                }
+            }
+
+            let tree = self.repo.find_tree(tree_id)?;
+            let parents = parents.iter()
+                .map(|id| self.repo.find_commit(*id))
+                .collect::<Result<Vec<_>, _>>()?;
+            let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+            let message = String::from_utf8_lossy(commit.message_bytes()).into_owned();
+            let new_id = self.repo.commit(None, &commit.author(), &commit.committer(), &message, &tree, &parent_refs)
+                .context("Failed to create imported commit")?;
+
+            rewritten.insert(commit.id(), new_id);
+            commits += 1;
+        }
+
+        Ok(rewritten.get(&head.id()).map(|&tip| ImportedHistory { tip, commits }))
+    }
+
+    /// Write the files of `tree` (in `source`) under their mapped paths as a tree in this repository
+    fn mapped_tree(&self, source: &Repository, tree: &git2::Tree, map: &dyn Fn(&str) -> Option<String>) -> Result<Oid> {
+        let mut index = Index::new()?;
+        let mut failure = None;
+
+        let walked = tree.walk(git2::TreeWalkMode::PreOrder, |dir, item| {
+            if !FILE_MODES.contains(&item.filemode()) {
+                return git2::TreeWalkResult::Ok;
+            }
+            let Some(vault_path) = item.name().and_then(|name| map(&format!("{}{}", dir, name))) else {
+                return git2::TreeWalkResult::Ok;
+            };
+            let copied = source.find_blob(item.id())
+                .and_then(|blob| self.repo.blob(blob.content()))
+                .and_then(|id| index.add(&index_entry(&vault_path, id, item.filemode() as u32)));
+            match copied {
+                Ok(()) => git2::TreeWalkResult::Ok,
+                Err(e) => {
+                    failure = Some(e);
+                    git2::TreeWalkResult::Abort
+                }
+            }
+        });
+        if let Some(e) = failure {
+            return Err(e).context("Failed to copy files from the imported repository");
+        }
+        walked?;
+
+        Ok(index.write_tree_to(&self.repo)?)
+    }
+
+    /// Bring imported history onto the current branch and check it out. An empty
+    /// repository takes it as is; otherwise a merge commit joins both histories,
+    /// keeping the current version of files that exist on both sides.
+    pub fn merge_imported(&self, history: &ImportedHistory, message: &str) -> Result<()> {
+        if self.is_empty()? {
+            // HEAD points to an unborn branch; make it the imported history
+            let branch = self.repo.find_reference("HEAD")?
+                .symbolic_target()
+                .context("HEAD is detached")?
+                .to_string();
+            self.repo.reference(&branch, history.tip, true, message)
+                .context("Failed to update branch")?;
+        } else {
+            let head = self.repo.head()?.peel_to_commit()?;
+            let imported = self.repo.find_commit(history.tip)?;
+
+            let mut index = Index::new()?;
+            index.read_tree(&imported.tree()?)?;
+            let mut failure = None;
+            let walked = head.tree()?.walk(git2::TreeWalkMode::PreOrder, |dir, item| {
+                if item.kind() != Some(git2::ObjectType::Blob) {
+                    return git2::TreeWalkResult::Ok;
+                }
+                let path = format!("{}{}", dir, item.name().unwrap_or_default());
+                match index.add(&index_entry(&path, item.id(), item.filemode() as u32)) {
+                    Ok(()) => git2::TreeWalkResult::Ok,
+                    Err(e) => {
+                        failure = Some(e);
+                        git2::TreeWalkResult::Abort
+                    }
+                }
+            });
+            if let Some(e) = failure {
+                return Err(e).context("Failed to merge imported files");
+            }
+            walked?;
+
+            let tree = self.repo.find_tree(index.write_tree_to(&self.repo)?)?;
+            let signature = git2::Signature::now("gfv", "gfv@local")?;
+            self.repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &[&head, &imported])
+                .context("Failed to create merge commit")?;
+        }
+
+        self.repo.checkout_head(Some(CheckoutBuilder::new().force()))
+            .context("Failed to check out imported files")?;
+        Ok(())
+    }
+}
+
+fn index_entry(path: &str, id: Oid, mode: u32) -> IndexEntry {
+    IndexEntry {
+        ctime: IndexTime::new(0, 0),
+        mtime: IndexTime::new(0, 0),
+        dev: 0,
+        ino: 0,
+        mode,
+        uid: 0,
+        gid: 0,
+        file_size: 0,
+        id,
+        flags: path.len().min(0xfff) as u16,
+        flags_extended: 0,
+        path: path.as_bytes().to_vec(),
+    }
+}
@@ -0,0 +1,162 @@
+// Repository checks and repairs used by `gfv doctor`
+
+use anyhow::{Context, Result};
+use git2::{build::CheckoutBuilder, RepositoryState};
+use std::collections::BTreeMap;
+
+use super::GitRepo;
+
+impl GitRepo {
+    /// Operation left unfinished in the repository (e.g. a rebase that failed part-way)
+    pub fn interrupted_operation(&self) -> Option<&'static str> {
+        match self.repo.state() {
+            RepositoryState::Clean => None,
+            RepositoryState::Merge => Some("merge"),
+            RepositoryState::Revert | RepositoryState::RevertSequence => Some("revert"),
+            RepositoryState::CherryPick | RepositoryState::CherryPickSequence => Some("cherry-pick"),
+            RepositoryState::Bisect => Some("bisect"),
+            RepositoryState::Rebase | RepositoryState::RebaseInteractive | RepositoryState::RebaseMerge => Some("rebase"),
+            RepositoryState::ApplyMailbox | RepositoryState::ApplyMailboxOrRebase => Some("patch application"),
+        }
+    }
+
+    /// Abort an unfinished operation, returning the branch and files to where they were before it
+    pub fn abort_operation(&self) -> Result<()> {
+        match self.repo.state() {
+            RepositoryState::Clean => return Ok(()),
+            RepositoryState::Rebase | RepositoryState::RebaseInteractive | RepositoryState::RebaseMerge => {
+                match self.repo.open_rebase(None) {
+                    Ok(mut rebase) => rebase.abort().context("Failed to abort rebase")?,
+                    // libgit2 can't open rebases started by `git rebase -i` and friends
+                    Err(_) => self.abort_rebase_by_hand()?,
+                }
+            }
+            RepositoryState::Bisect => self.abort_bisect()?,
+            _ => {
+                // Like `git merge --abort`: reset only what the operation touched,
+                // keeping uncommitted changes to other files
+                let paths = self.operation_paths()?;
+                if !paths.is_empty() {
+                    let mut checkout = CheckoutBuilder::new();
+                    checkout.force();
+                    for path in &paths {
+                        checkout.path(path);
+                    }
+                    self.repo.checkout_head(Some(&mut checkout))
+                        .context("Failed to check out HEAD")?;
+                }
+                self.repo.cleanup_state()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Paths staged or left in conflict by a merge, revert or cherry-pick. Git only starts
+    /// these with a clean index, so everything staged belongs to the operation.
+    fn operation_paths(&self) -> Result<Vec<String>> {
+        let index = self.repo.index()?;
+        let head = self.repo.head()?.peel_to_tree()?;
+        let diff = self.repo.diff_tree_to_index(Some(&head), Some(&index), None)?;
+
+        let mut paths: Vec<String> = diff.deltas()
+            .flat_map(|delta| [delta.old_file().path(), delta.new_file().path()])
+            .flatten()
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect();
+        for conflict in index.conflicts()? {
+            let conflict = conflict?;
+            for entry in [conflict.ancestor, conflict.our, conflict.their].into_iter().flatten() {
+                paths.push(String::from_utf8_lossy(&entry.path).into_owned());
+            }
+        }
+        paths.sort();
+        paths.dedup();
+        Ok(paths)
+    }
+
+    /// Like `git bisect reset`: go back to the branch (or commit) the bisect started
+    /// from, keeping uncommitted changes, and drop the bisect state
+    fn abort_bisect(&self) -> Result<()> {
+        let git_dir = self.repo.path();
+        let start = std::fs::read_to_string(git_dir.join("BISECT_START"))
+            .context("Bisect state not found")?
+            .trim()
+            .to_string();
+
+        let branch = format!("refs/heads/{}", start);
+        let (commit, branch) = match self.repo.find_reference(&branch) {
+            Ok(reference) => (reference.peel_to_commit()?, Some(branch)),
+            Err(_) => (self.repo.find_commit(git2::Oid::from_str(&start)?)?, None),
+        };
+        self.repo.checkout_tree(commit.as_object(), Some(CheckoutBuilder::new().safe()))
+            .context("Failed to check out the commit the bisect started from; commit or stash local changes first")?;
+        match branch {
+            Some(branch) => self.repo.set_head(&branch)?,
+            None => self.repo.set_head_detached(commit.id())?,
+        }
+
+        for file in ["BISECT_START", "BISECT_LOG", "BISECT_TERMS", "BISECT_NAMES",
+            "BISECT_EXPECTED_REV", "BISECT_ANCESTORS_OK", "BISECT_RUN", "BISECT_FIRST_PARENT"]
+        {
+            let _ = std::fs::remove_file(git_dir.join(file));
+        }
+        for reference in self.repo.references_glob("refs/bisect/*")? {
+            reference?.delete()?;
+        }
+        Ok(())
+    }
+
+    /// Put the branch back where the rebase found it, from the state git keeps
+    /// in `.git/rebase-merge` (or `.git/rebase-apply`)
+    fn abort_rebase_by_hand(&self) -> Result<()> {
+        let state_dir = ["rebase-merge", "rebase-apply"].iter()
+            .map(|dir| self.repo.path().join(dir))
+            .find(|dir| dir.is_dir())
+            .context("Rebase state not found")?;
+        let read = |name: &str| {
+            std::fs::read_to_string(state_dir.join(name))
+                .map(|value| value.trim().to_string())
+                .with_context(|| format!("Failed to read rebase state ({})", name))
+        };
+        let branch = read("head-name")?;
+        let orig_head = git2::Oid::from_str(&read("orig-head")?)?;
+
+        if branch.starts_with("refs/heads/") {
+            self.repo.reference(&branch, orig_head, true, "rebase: aborting")?;
+            self.repo.set_head(&branch)?;
+        } else {
+            self.repo.set_head_detached(orig_head)?;
+        }
+        self.repo.checkout_head(Some(CheckoutBuilder::new().force()))
+            .context("Failed to check out HEAD")?;
+        std::fs::remove_dir_all(&state_dir)
+            .context("Failed to remove rebase state")?;
+        self.repo.cleanup_state()?;
+        Ok(())
+    }
+
+    /// Remotes configured in the repository, with their URLs
+    pub fn remote_urls(&self) -> Result<BTreeMap<String, Option<String>>> {
+        let mut urls = BTreeMap::new();
+        for name in self.repo.remotes()?.iter().flatten() {
+            let remote = self.repo.find_remote(name)?;
+            urls.insert(name.to_string(), remote.url().map(str::to_string));
+        }
+        Ok(urls)
+    }
+
+    /// Whether the last commit has a file or directory at `path`
+    pub fn head_has_path(&self, path: &str) -> bool {
+        self.repo.head()
+            .and_then(|head| head.peel_to_tree())
+            .and_then(|tree| tree.get_path(std::path::Path::new(path)))
+            .is_ok()
+    }
+
+    /// Check out `path` (a file or directory) from the last commit
+    pub fn checkout_path(&self, path: &str) -> Result<()> {
+        self.repo.checkout_head(Some(CheckoutBuilder::new().force().path(path)))
+            .with_context(|| format!("Failed to check out {}", path))?;
+        Ok(())
+    }
+}
@@ -3,42 +3,74 @@ mod vault;
 mod config;
 mod git_ops;
 mod commands;
+mod catalog;
 #[cfg(feature = "ai")]
 mod ai;
 mod error;
+mod output;
 
 use anyhow::Result;
 use clap::Parser;
+use std::path::PathBuf;
 use cli::Cli;
 use config::Config;
 
 fn main() -> Result<()> {
-    // Load config to check for aliases
-    let config = Config::load().unwrap_or_else(|_| Config {
-        vaults: std::collections::HashMap::new(),
-        current: config::CurrentConfig {
-            active: "default".to_string(),
-        },
-        ai: Default::default(),
-        sync: Default::default(),
-        aliases: std::collections::HashMap::new(),
-    });
+    let args: Vec<String> = std::env::args().collect();
+
+    // --config must be known before the config is loaded for alias resolution
+    if let Some(path) = config_arg(&args) {
+        config::set_config_override(path);
+    }
+
+    // Load config to check for aliases and ui settings
+    let config = Config::load_effective().unwrap_or_default();
+    output::init_terminal(&config.ui);
 
     // Resolve aliases in command line arguments
-    let args = resolve_aliases(&config);
+    let args = resolve_aliases(&config, args);
 
     let cli = Cli::parse_from(args);
     cli.execute()
 }
 
-/// Resolve aliases in command line arguments
-fn resolve_aliases(config: &Config) -> Vec<String> {
-    let mut args: Vec<String> = std::env::args().collect();
+/// Global options that take a value, skipped when looking for the command name
+const GLOBAL_VALUE_OPTIONS: [&str; 2] = ["--config", "--format"];
 
-    // Only process if we have at least 2 arguments (program name + command)
-    if args.len() >= 2 {
-        let command = &args[1];
+/// Find the value of the global `--config` option, if given
+fn config_arg(args: &[String]) -> Option<PathBuf> {
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        if arg == "--" {
+            break;
+        }
+        if arg == "--config" {
+            return iter.next().map(PathBuf::from);
+        }
+        if let Some(value) = arg.strip_prefix("--config=") {
+            return Some(PathBuf::from(value));
+        }
+    }
+    None
+}
+
+/// Resolve aliases in command line arguments
+fn resolve_aliases(config: &Config, mut args: Vec<String>) -> Vec<String> {
+    // The command is the first argument that is not a global option
+    let mut index = 1;
+    while index < args.len() {
+        let arg = &args[index];
+        if GLOBAL_VALUE_OPTIONS.contains(&arg.as_str()) {
+            index += 2;
+        } else if arg.starts_with('-') {
+            index += 1;
+        } else {
+            break;
+        }
+    }
 
+    // Only process if there is a command
+    if let Some(command) = args.get(index) {
         // Check if this is an alias
         if let Some(expansion) = config.aliases.get(command) {
             // Replace the command with its expansion
@@ -48,9 +80,9 @@ fn resolve_aliases(config: &Config) -> Vec<String> {
                 .collect();
 
             // Remove the original command and insert expansion
-            args.remove(1);
+            args.remove(index);
             for (i, part) in expansion_parts.into_iter().enumerate() {
-                args.insert(1 + i, part);
+                args.insert(index + i, part);
             }
         }
     }
@@ -0,0 +1,53 @@
+// Output module - output format selection and terminal detection
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::config::UiConfig;
+
+static EMOJI: AtomicBool = AtomicBool::new(true);
+
+/// Output format for read commands
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text
+    #[default]
+    Text,
+    /// Machine-readable JSON (see docs/JSON_OUTPUT.md)
+    Json,
+}
+
+impl OutputFormat {
+    pub fn is_json(self) -> bool {
+        self == OutputFormat::Json
+    }
+}
+
+/// Apply `ui.*` settings. With `ui.color = auto`, colors are off when stdout
+/// is not a terminal (pipes, scripts, files).
+pub fn init_terminal(ui: &UiConfig) {
+    let is_terminal = std::io::stdout().is_terminal();
+    match ui.color.as_str() {
+        "always" => colored::control::set_override(true),
+        "never" => colored::control::set_override(false),
+        _ if !is_terminal => colored::control::set_override(false),
+        _ => {}
+    }
+    EMOJI.store(ui.emoji && is_terminal, Ordering::Relaxed);
+}
+
+/// Emoji are only used when writing to a terminal and `ui.emoji` is on
+pub fn use_emoji() -> bool {
+    EMOJI.load(Ordering::Relaxed)
+}
+
+/// Print a value as pretty JSON on stdout
+pub fn print_json<T: Serialize>(value: &T) -> Result<()> {
+    let json = serde_json::to_string_pretty(value)
+        .context("Failed to serialize JSON output")?;
+    println!("{}", json);
+    Ok(())
+}
@@ -1,15 +1,19 @@
 // Vault module - manages the vault operations
 
+pub mod commit_message;
 pub mod manifest;
 
 use anyhow::Result;
 use std::path::{Path, PathBuf};
 use manifest::Manifest;
+use crate::config::Config;
 
 pub struct Vault {
     pub vault_dir: PathBuf,    // ~/.gfv/default/
     pub repo_path: PathBuf,     // ~/.gfv/default/repo/
     pub manifest: Manifest,
+    /// Effective settings: env > vault config > repo/.gfv.toml > global > defaults
+    pub settings: Config,
 }
 
 impl Vault {
@@ -17,11 +21,13 @@ impl Vault {
     pub fn load(vault_dir: &Path) -> Result<Self> {
         let repo_path = vault_dir.join("repo");
         let manifest = Manifest::load(vault_dir)?;
+        let settings = Config::load_layered(Some(vault_dir))?;
 
         Ok(Self {
             vault_dir: vault_dir.to_path_buf(),
             repo_path,
             manifest,
+            settings,
         })
     }
 
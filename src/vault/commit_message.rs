@@ -0,0 +1,244 @@
+// Commit messages - describes a backup in terms of vault paths, without AI
+
+use std::collections::BTreeMap;
+
+use super::manifest::{is_within, Manifest};
+use crate::config::BackupConfig;
+use crate::git_ops::FileDiff;
+
+/// Default for `backup.message_template`
+pub const DEFAULT_TEMPLATE: &str = "{verb} {paths} ({counts})";
+
+/// Vault paths named in the subject before the rest are counted
+const MAX_NAMED_PATHS: usize = 3;
+
+/// How a vault path changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Change {
+    Added,
+    Modified,
+    Removed,
+}
+
+impl Change {
+    fn from_status(status: char) -> Self {
+        match status {
+            'A' | 'C' => Change::Added,
+            'D' => Change::Removed,
+            _ => Change::Modified,
+        }
+    }
+
+    fn marker(self) -> char {
+        match self {
+            Change::Added => 'A',
+            Change::Modified => 'M',
+            Change::Removed => 'D',
+        }
+    }
+
+    /// A path whose files changed in different ways was modified
+    fn combine(self, other: Change) -> Change {
+        if self == other { self } else { Change::Modified }
+    }
+}
+
+/// Whether a vault path is in the last commit and in the commit being made
+pub struct Presence {
+    pub before: bool,
+    pub after: bool,
+}
+
+/// Describe changed files as a commit message, grouped by vault path:
+/// a subject from `backup.message_template` (or the conventional-commit style)
+/// and, when more than one path changed, a body listing every path.
+/// Returns `None` when there are no changes.
+pub fn commit_message(
+    files: &[FileDiff],
+    manifest: &Manifest,
+    config: &BackupConfig,
+    presence: impl Fn(&str) -> Presence,
+) -> Option<String> {
+    let changes = classify(files, manifest, presence);
+    if changes.is_empty() {
+        return None;
+    }
+
+    let count = |kind: Change| changes.values().filter(|&&c| c == kind).count();
+    let (added, modified, removed) = (count(Change::Added), count(Change::Modified), count(Change::Removed));
+
+    let verb = if added == changes.len() {
+        "Add"
+    } else if removed == changes.len() {
+        "Remove"
+    } else {
+        "Update"
+    };
+
+    let counts: Vec<String> = [(added, "added"), (modified, "modified"), (removed, "removed")]
+        .iter()
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, label)| format!("{} {}", n, label))
+        .collect();
+
+    let names: Vec<&str> = changes.keys().map(String::as_str).collect();
+    let description = config.message_template
+        .replace("{verb}", verb)
+        .replace("{paths}", &join_paths(&names))
+        .replace("{counts}", &counts.join(", "))
+        .replace("{added}", &added.to_string())
+        .replace("{modified}", &modified.to_string())
+        .replace("{removed}", &removed.to_string())
+        .replace("{total}", &changes.len().to_string());
+    let description = description.trim();
+
+    let mut message = if config.message_style == "conventional" {
+        conventional_subject(description, &names)
+    } else {
+        description.to_string()
+    };
+
+    if changes.len() > 1 {
+        message.push_str("\n\n");
+        let lines: Vec<String> = changes.iter()
+            .map(|(path, change)| format!("{} {}", change.marker(), path))
+            .collect();
+        message.push_str(&lines.join("\n"));
+    }
+
+    Some(message)
+}
+
+/// Group changed repo files by the vault path (manifest entry) they belong to.
+/// A file's change is the entry's change; a directory entry is added or removed
+/// only as a whole, and updated when some of its files change.
+fn classify(files: &[FileDiff], manifest: &Manifest, presence: impl Fn(&str) -> Presence) -> BTreeMap<String, Change> {
+    let mut changes: BTreeMap<String, Change> = BTreeMap::new();
+    let mut record = |path: &str, change: Change| {
+        let name = vault_path_for(path, manifest).unwrap_or(path).to_string();
+        changes.entry(name)
+            .and_modify(|existing| *existing = existing.combine(change))
+            .or_insert(change);
+    };
+
+    for file in files {
+        match (file.status, &file.old_path) {
+            // A rename within one vault path modifies it; across paths it moves content
+            ('R', Some(old_path)) => {
+                record(old_path, Change::Removed);
+                record(&file.path, Change::Added);
+            }
+            (status, _) => record(&file.path, Change::from_status(status)),
+        }
+    }
+
+    for (name, change) in changes.iter_mut() {
+        if manifest.get_file(name).is_some_and(|entry| entry.file_type == "directory") {
+            *change = match presence(name) {
+                Presence { before: false, .. } => Change::Added,
+                Presence { after: false, .. } => Change::Removed,
+                _ => Change::Modified,
+            };
+        }
+    }
+
+    changes
+}
+
+/// The manifest entry containing a repo path (longest match), if any
+fn vault_path_for<'a>(path: &str, manifest: &'a Manifest) -> Option<&'a str> {
+    manifest.files.keys()
+        .filter(|vault_path| is_within(path, vault_path))
+        .max_by_key(|vault_path| vault_path.len())
+        .map(String::as_str)
+}
+
+/// `a`, `a and b`, `a, b and c`, `a, b, c and 2 more`
+fn join_paths(names: &[&str]) -> String {
+    if names.len() > MAX_NAMED_PATHS {
+        let rest = names.len() - MAX_NAMED_PATHS;
+        return format!("{} and {} more", names[..MAX_NAMED_PATHS].join(", "), rest);
+    }
+    match names.split_last() {
+        Some((last, [])) => last.to_string(),
+        Some((last, init)) => format!("{} and {}", init.join(", "), last),
+        None => String::new(),
+    }
+}
+
+/// `chore(<scope>): <description>`, scoped to the top-level directory shared by
+/// all changed paths, if there is one
+fn conventional_subject(description: &str, names: &[&str]) -> String {
+    let top = |name: &str| name.split('/').next().unwrap_or(name).to_string();
+    let scope = names.first()
+        .map(|first| top(first))
+        .filter(|scope| names.iter().all(|name| top(name) == *scope));
+
+    let mut chars = description.chars();
+    let description = match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    };
+
+    match scope {
+        Some(scope) => format!("chore({}): {}", scope, description),
+        None => format!("chore: {}", description),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vault::manifest::FileEntry;
+
+    fn manifest() -> Manifest {
+        let mut manifest = Manifest::new(None);
+        for (vault_path, file_type) in [("nvim", "directory"), ("zsh/zshrc", "file")] {
+            manifest.add_file(vault_path.to_string(), FileEntry {
+                source_path: format!("/home/me/{}", vault_path),
+                file_type: file_type.to_string(),
+                platform: None,
+                added_at: chrono::Utc::now(),
+                last_sync: None,
+                sensitive: false,
+            });
+        }
+        manifest
+    }
+
+    fn diff(path: &str, status: char) -> FileDiff {
+        FileDiff {
+            path: path.to_string(),
+            old_path: None,
+            status,
+            added: 1,
+            removed: 0,
+            binary: false,
+            patch: String::new(),
+        }
+    }
+
+    #[test]
+    fn directory_entries_change_as_a_whole() {
+        let cases = [
+            // Files in the directory, was it in the last commit, is it in the new one
+            (vec![diff("nvim/extra.lua", 'A')], true, true, Change::Modified),
+            (vec![diff("nvim/extra.lua", 'D')], true, true, Change::Modified),
+            (vec![diff("nvim/init.lua", 'A'), diff("nvim/extra.lua", 'A')], false, true, Change::Added),
+            (vec![diff("nvim/init.lua", 'D')], true, false, Change::Removed),
+        ];
+        for (files, before, after, expected) in cases {
+            let changes = classify(&files, &manifest(), |_| Presence { before, after });
+            assert_eq!(changes["nvim"], expected, "{:?} before={} after={}",
+                files.iter().map(|f| (&f.path, f.status)).collect::<Vec<_>>(), before, after);
+        }
+    }
+
+    #[test]
+    fn file_entries_keep_their_status() {
+        let files = [diff("zsh/zshrc", 'A'), diff("stray.txt", 'D')];
+        let changes = classify(&files, &manifest(), |_| Presence { before: true, after: true });
+        assert_eq!(changes["zsh/zshrc"], Change::Added);
+        assert_eq!(changes["stray.txt"], Change::Removed);
+    }
+}
@@ -1,20 +1,30 @@
 // Manifest module - manages manifest.json
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 use chrono::{DateTime, Utc};
 use anyhow::{Context, Result};
 
+/// Name of the remote created for single-remote vaults
+pub const DEFAULT_REMOTE: &str = "origin";
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Manifest {
     pub version: String,
     pub files: HashMap<String, FileEntry>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub remote: Option<RemoteConfig>,
+    /// Single remote written by older versions; migrated into `remotes` on load
+    #[serde(default, skip_serializing)]
+    remote: Option<RemoteConfig>,
+    /// Named remotes, keyed by their git remote name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub remotes: BTreeMap<String, RemoteConfig>,
+    /// Remote that restore pulls from and backup rebases onto
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primary: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileEntry {
     #[serde(rename = "sourcePath")]
     pub source_path: String,
@@ -26,21 +36,44 @@ pub struct FileEntry {
     pub added_at: DateTime<Utc>,
     #[serde(rename = "lastSync", skip_serializing_if = "Option::is_none")]
     pub last_sync: Option<DateTime<Utc>>,
+    /// Contains secrets: never sent to AI providers
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub sensitive: bool,
 }
 
+/// File entries as carried to another machine (in bundles): source paths under
+/// the home directory are stored as `~/...`, remotes are left out
 #[derive(Debug, Serialize, Deserialize)]
+pub struct SharedFiles {
+    pub version: String,
+    pub files: BTreeMap<String, FileEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RemoteConfig {
     pub url: String,
     pub branch: String,
+    /// SSH private key used for this vault's remote (overrides key discovery)
+    #[serde(rename = "sshKey", default, skip_serializing_if = "Option::is_none")]
+    pub ssh_key: Option<String>,
+    /// Environment variable holding an HTTPS token for this vault's remote
+    #[serde(rename = "tokenEnv", default, skip_serializing_if = "Option::is_none")]
+    pub token_env: Option<String>,
 }
 
 impl Manifest {
     pub fn new(remote: Option<RemoteConfig>) -> Self {
-        Self {
+        let mut manifest = Self {
             version: "1.0".to_string(),
             files: HashMap::new(),
-            remote,
+            remote: None,
+            remotes: BTreeMap::new(),
+            primary: None,
+        };
+        if let Some(remote) = remote {
+            manifest.add_remote(DEFAULT_REMOTE.to_string(), remote);
         }
+        manifest
     }
 
     /// Load manifest from manifest.json in the vault directory
@@ -55,9 +88,16 @@ impl Manifest {
         let content = std::fs::read_to_string(&manifest_path)
             .context("Failed to read manifest file")?;
 
-        let manifest: Manifest = serde_json::from_str(&content)
+        let mut manifest: Manifest = serde_json::from_str(&content)
             .context("Failed to parse manifest JSON")?;
 
+        // Migrate the legacy single remote
+        if let Some(remote) = manifest.remote.take() {
+            if manifest.remotes.is_empty() {
+                manifest.add_remote(DEFAULT_REMOTE.to_string(), remote);
+            }
+        }
+
         Ok(manifest)
     }
 
@@ -85,4 +125,77 @@ impl Manifest {
     pub fn get_file(&self, vault_path: &str) -> Option<&FileEntry> {
         self.files.get(vault_path)
     }
+
+    /// Get the primary remote (explicitly designated, or the only/first one)
+    pub fn primary_remote(&self) -> Option<(&str, &RemoteConfig)> {
+        self.primary
+            .as_ref()
+            .and_then(|name| self.remotes.get_key_value(name))
+            .or_else(|| self.remotes.iter().next())
+            .map(|(name, remote)| (name.as_str(), remote))
+    }
+
+    /// Get the primary remote for modification
+    pub fn primary_remote_mut(&mut self) -> Option<&mut RemoteConfig> {
+        let name = self.primary_remote()?.0.to_string();
+        self.remotes.get_mut(&name)
+    }
+
+    /// Add or replace a named remote; the first remote becomes primary
+    pub fn add_remote(&mut self, name: String, remote: RemoteConfig) {
+        if self.primary.is_none() {
+            self.primary = Some(name.clone());
+        }
+        self.remotes.insert(name, remote);
+    }
+
+    /// Remove a named remote, promoting another one if it was primary
+    pub fn remove_remote(&mut self, name: &str) -> Option<RemoteConfig> {
+        let removed = self.remotes.remove(name)?;
+        if self.primary.as_deref() == Some(name) {
+            self.primary = self.remotes.keys().next().cloned();
+        }
+        Some(removed)
+    }
+
+    pub fn has_remote(&self) -> bool {
+        !self.remotes.is_empty()
+    }
+
+    /// File entries to carry to another machine, with home-relative source paths
+    pub fn shared_files(&self, home: &Path) -> SharedFiles {
+        let files = self.files.iter()
+            .map(|(vault_path, entry)| {
+                let mut entry = entry.clone();
+                if let Ok(relative) = Path::new(&entry.source_path).strip_prefix(home) {
+                    entry.source_path = format!("~/{}", relative.to_string_lossy().replace('\\', "/"));
+                }
+                (vault_path.clone(), entry)
+            })
+            .collect();
+        SharedFiles { version: self.version.clone(), files }
+    }
+
+    /// Add entries from another machine that are not in this manifest yet;
+    /// existing entries are kept as they are. Returns the added vault paths.
+    pub fn add_shared_files(&mut self, shared: SharedFiles, home: &Path) -> Vec<String> {
+        let mut added = Vec::new();
+        for (vault_path, mut entry) in shared.files {
+            if self.files.contains_key(&vault_path) {
+                continue;
+            }
+            if let Some(relative) = entry.source_path.strip_prefix("~/") {
+                entry.source_path = home.join(relative).to_string_lossy().into_owned();
+            }
+            entry.last_sync = None;
+            added.push(vault_path.clone());
+            self.files.insert(vault_path, entry);
+        }
+        added
+    }
+}
+
+/// Whether `path` is the vault path `entry` or lies inside it
+pub fn is_within(path: &str, entry: &str) -> bool {
+    path == entry || path.strip_prefix(entry).is_some_and(|rest| rest.starts_with('/'))
 }
@@ -0,0 +1,52 @@
+// What gfv writes to the global config file
+
+use assert_cmd::Command;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// Run gfv with `home` as the home directory and `gfv_home` as the gfv home
+fn gfv(home: &Path, gfv_home: &Path, args: &[&str]) -> String {
+    let output = Command::cargo_bin("gfv").unwrap()
+        .env("HOME", home)
+        .env("GFV_HOME", gfv_home)
+        .env("NO_COLOR", "1")
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "gfv {:?} failed: {}", args, String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn vault_create_writes_no_default_settings() {
+    let home = TempDir::new().unwrap();
+    let home = home.path();
+    let gfv_home = home.join("gfv");
+
+    gfv(home, &gfv_home, &["vault", "create", "work"]);
+
+    let config: toml::Table = std::fs::read_to_string(gfv_home.join("config.toml")).unwrap()
+        .parse().unwrap();
+    assert!(config["vaults"].as_table().unwrap().contains_key("work"));
+    for section in ["ai", "sync", "ui", "backup"] {
+        assert!(!config.contains_key(section), "config.toml has a [{}] table:\n{:#?}", section, config);
+    }
+
+    let origins = gfv(home, &gfv_home, &["config", "--list", "--show-origin"]);
+    assert!(!origins.contains("global"), "defaults reported as global:\n{}", origins);
+}
+
+#[test]
+fn saving_keeps_settings_set_with_config() {
+    let home = TempDir::new().unwrap();
+    let home = home.path();
+    let gfv_home = home.join("gfv");
+
+    gfv(home, &gfv_home, &["config", "ai.model", "gpt-4o-mini"]);
+    gfv(home, &gfv_home, &["vault", "create", "work"]);
+
+    let config: toml::Table = std::fs::read_to_string(gfv_home.join("config.toml")).unwrap()
+        .parse().unwrap();
+    assert_eq!(config["ai"]["model"].as_str(), Some("gpt-4o-mini"));
+    assert_eq!(config["ai"].as_table().unwrap().len(), 1);
+}
@@ -0,0 +1,59 @@
+// Backups to a vault with a mirror remote, against local bare repositories
+
+use assert_cmd::Command;
+use git2::Repository;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// Run gfv with `home` as the home directory and return its stdout
+fn gfv(home: &Path, args: &[&str]) -> String {
+    let output = Command::cargo_bin("gfv").unwrap()
+        .env("HOME", home)
+        .env("NO_COLOR", "1")
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "gfv {:?} failed: {}", args, String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Commit `branch` points to in a repository, if the branch exists
+fn branch_tip(repo: &Path, branch: &str) -> Option<git2::Oid> {
+    Repository::open(repo).unwrap()
+        .refname_to_id(&format!("refs/heads/{}", branch))
+        .ok()
+}
+
+#[test]
+fn backup_pushes_primary_first_then_mirror() {
+    let home = TempDir::new().unwrap();
+    let home = home.path();
+    let primary = home.join("primary.git");
+    let mirror = home.join("mirror.git");
+    Repository::init_bare(&primary).unwrap();
+    Repository::init_bare(&mirror).unwrap();
+
+    gfv(home, &["init"]);
+    gfv(home, &["vault", "set-remote", primary.to_str().unwrap()]);
+    // Sorts before "origin", so the order below comes from the primary going first
+    gfv(home, &["vault", "remote", "add", "backup", mirror.to_str().unwrap()]);
+
+    let zshrc = home.join(".zshrc");
+    std::fs::write(&zshrc, "export EDITOR=vim\n").unwrap();
+    gfv(home, &["link", zshrc.to_str().unwrap()]);
+
+    let local = home.join(".gfv/default/repo");
+    for message in ["First backup", "Second backup"] {
+        let output = gfv(home, &["backup", "-m", message]);
+
+        let primary_push = output.find("Pushed to origin/main").expect("pushed to the primary");
+        let mirror_push = output.find("Pushed to backup/main").expect("pushed to the mirror");
+        assert!(primary_push < mirror_push, "primary must be pushed before the mirror:\n{}", output);
+
+        let head = branch_tip(&local, "main").unwrap();
+        assert_eq!(branch_tip(&primary, "main"), Some(head));
+        assert_eq!(branch_tip(&mirror, "main"), Some(head));
+
+        std::fs::write(&zshrc, "export EDITOR=nvim\n").unwrap();
+    }
+}
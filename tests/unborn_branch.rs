@@ -0,0 +1,69 @@
+// Remote status and restore previews on a vault with nothing committed yet
+
+use assert_cmd::Command;
+use git2::Repository;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// Run gfv with `home` as the home directory and return its stdout
+fn gfv(home: &Path, args: &[&str]) -> String {
+    let output = Command::cargo_bin("gfv").unwrap()
+        .env("HOME", home)
+        .env("NO_COLOR", "1")
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "gfv {:?} failed: {}", args, String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// A bare remote with one backup of ~/.zshrc in it, and a second machine whose
+/// vault points at that remote but has no commits on its branch
+fn remote_and_new_vault(root: &Path) -> std::path::PathBuf {
+    let remote = root.join("remote.git");
+    Repository::init_bare(&remote).unwrap();
+
+    let first = root.join("first");
+    std::fs::create_dir(&first).unwrap();
+    gfv(&first, &["init"]);
+    gfv(&first, &["vault", "set-remote", remote.to_str().unwrap()]);
+    let zshrc = first.join(".zshrc");
+    std::fs::write(&zshrc, "export EDITOR=vim\n").unwrap();
+    gfv(&first, &["link", zshrc.to_str().unwrap()]);
+    gfv(&first, &["backup", "-m", "Add zshrc"]);
+
+    let second = root.join("second");
+    std::fs::create_dir(&second).unwrap();
+    gfv(&second, &["init"]);
+    gfv(&second, &["vault", "set-remote", remote.to_str().unwrap()]);
+    // Drop the commit vault creation makes, leaving HEAD on an unborn branch
+    let repo = Repository::open(second.join(".gfv/default/repo")).unwrap();
+    repo.find_reference("refs/heads/main").unwrap().delete().unwrap();
+    std::fs::remove_file(second.join(".gfv/default/repo/.gitignore")).unwrap();
+    let mut index = repo.index().unwrap();
+    index.clear().unwrap();
+    index.write().unwrap();
+    second
+}
+
+#[test]
+fn status_remote_reports_nothing_ahead() {
+    let root = TempDir::new().unwrap();
+    let second = remote_and_new_vault(root.path());
+
+    let output = gfv(&second, &["status", "--remote", "--format", "json"]);
+    let status: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let remote = &status["remoteStatus"];
+    assert_eq!(remote["branchExists"], true);
+    assert_eq!(remote["ahead"], 0);
+    assert_eq!(remote["behind"], 2);
+}
+
+#[test]
+fn restore_dry_run_previews_the_first_pull() {
+    let root = TempDir::new().unwrap();
+    let second = remote_and_new_vault(root.path());
+
+    let output = gfv(&second, &["restore", "--dry-run"]);
+    assert!(output.contains("Pull would bring in 2 commits"), "{}", output);
+}